@@ -0,0 +1,861 @@
+//! everything a help run needs, built once and reused for every topic.
+//!
+//! ```ignore
+//! let help = HelpContext::builder(Cli::command())
+//!     .app_name("demo")
+//!     .version(env!("CARGO_PKG_VERSION"))
+//!     .docs(docs)
+//!     .theme(HelpTheme::default(Color::Blue))
+//!     .build();
+//!
+//! help.run(&args)?;
+//! ```
+
+use std::io::{IsTerminal, Write};
+
+use clap::Command;
+use termimad::crossterm::style::{Color, Stylize};
+
+use crate::error::{HelpError, UnknownTopic};
+use crate::help_command::{resolve_help, HelpArgs, HelpTarget};
+use crate::help_page::{HelpArgumentPage, HelpGuideEntry, HelpPage, HelpTreeNode, HelpVerbosity};
+use crate::pager::PagerPolicy;
+use crate::relations;
+use crate::renderer::{self, OutputPolicy};
+use crate::styled;
+use crate::suggest::did_you_mean;
+use crate::theme::ColorPolicy;
+use crate::{DocRegistry, HelpTheme};
+
+/// markdown for a topic, and how to lay it out.
+enum Markdown {
+    /// a generated page, with a left-aligned title.
+    Page(String),
+    /// a guide, laid out the way its author wrote it.
+    Guide(String),
+}
+
+/// app metadata, the clap tree, docs and presentation settings for running help topics.
+#[derive(Debug, Clone)]
+pub struct HelpContext {
+    app_name: String,
+    bin_name: String,
+    version: Option<String>,
+    root: Command,
+    /// `root` after `Command::build`, for propagated globals and full usage lines. pages, topics
+    /// and argument lookups all go through it.
+    built: Command,
+    docs: DocRegistry,
+    theme: HelpTheme,
+    output: OutputPolicy,
+    pager: PagerPolicy,
+    width: Option<usize>,
+    show_hidden: bool,
+    show_env_values: bool,
+    verbosity: HelpVerbosity,
+}
+
+/// builder for [`HelpContext`], see [`HelpContext::builder`].
+#[derive(Debug, Clone)]
+pub struct HelpContextBuilder {
+    app_name: Option<String>,
+    bin_name: Option<String>,
+    version: Option<String>,
+    root: Command,
+    docs: DocRegistry,
+    theme: Option<HelpTheme>,
+    color: Option<ColorPolicy>,
+    output: OutputPolicy,
+    pager: PagerPolicy,
+    width: Option<usize>,
+    show_hidden: bool,
+    show_env_values: bool,
+    verbosity: HelpVerbosity,
+}
+
+impl HelpContextBuilder {
+    /// name shown on the program page and in titles (defaults to the root's display name).
+    pub fn app_name(mut self, app_name: impl Into<String>) -> Self {
+        self.app_name = Some(app_name.into());
+        self
+    }
+
+    /// binary name used in usage lines and subcommand titles (defaults to clap's bin name, then
+    /// the executable's file name).
+    pub fn bin_name(mut self, bin_name: impl Into<String>) -> Self {
+        self.bin_name = Some(bin_name.into());
+        self
+    }
+
+    /// version shown next to the app name on the program page (none by default).
+    pub fn version(mut self, version: impl Into<String>) -> Self {
+        self.version = Some(version.into());
+        self
+    }
+
+    /// extra docs and guides (empty by default).
+    pub fn docs(mut self, docs: DocRegistry) -> Self {
+        self.docs = docs;
+        self
+    }
+
+    /// colors (defaults to [`HelpTheme::default`] with a blue accent).
+    pub fn theme(mut self, theme: HelpTheme) -> Self {
+        self.theme = Some(theme);
+        self
+    }
+
+    /// override the theme's [`ColorPolicy`], e.g. with clap's `ColorChoice` (`.color(choice.into())`).
+    pub fn color(mut self, color: ColorPolicy) -> Self {
+        self.color = Some(color);
+        self
+    }
+
+    /// whether pages scroll, print, or pick based on their height (the default).
+    pub fn output(mut self, output: OutputPolicy) -> Self {
+        self.output = output;
+        self
+    }
+
+    /// which pager long pages go through (the built-in view by default).
+    pub fn pager(mut self, pager: PagerPolicy) -> Self {
+        self.pager = pager;
+        self
+    }
+
+    /// wrap width for plain text when stdout isn't a terminal (defaults to 80 columns).
+    pub fn width(mut self, width: usize) -> Self {
+        self.width = Some(width);
+        self
+    }
+
+    /// also list hidden subcommands and arguments (dimmed, in their own section). the
+    /// `CHKC_HELP_SHOW_HIDDEN` env var and `help --hidden` turn it on too.
+    pub fn show_hidden(mut self, show_hidden: bool) -> Self {
+        self.show_hidden = show_hidden;
+        self
+    }
+
+    /// print the current value of env vars bound to arguments, not just whether they're set.
+    /// off by default: help ends up in terminals, logs and bug reports, and the value may be a
+    /// token.
+    pub fn show_env_values(mut self, show_env_values: bool) -> Self {
+        self.show_env_values = show_env_values;
+        self
+    }
+
+    /// terse one-liners or the full long help (the default). `help --short` / `--long` override
+    /// it per run.
+    pub fn verbosity(mut self, verbosity: HelpVerbosity) -> Self {
+        self.verbosity = verbosity;
+        self
+    }
+
+    /// fill in the defaults and build the clap tree once, so every page sees its globals.
+    pub fn build(self) -> HelpContext {
+        let app_name = self.app_name.unwrap_or_else(|| {
+            self.root
+                .get_display_name()
+                .unwrap_or(self.root.get_name())
+                .to_string()
+        });
+
+        let bin_name = self
+            .bin_name
+            .or_else(|| self.root.get_bin_name().map(str::to_string))
+            .or_else(exe_name)
+            .unwrap_or_else(|| self.root.get_name().to_string());
+
+        let mut theme = self
+            .theme
+            .unwrap_or_else(|| HelpTheme::default(Color::Blue));
+        if let Some(color) = self.color {
+            theme.color = color;
+        }
+
+        let mut built = help_tree(self.root.clone()).bin_name(&bin_name);
+        built.build();
+
+        HelpContext {
+            app_name,
+            bin_name,
+            version: self.version,
+            root: self.root,
+            built,
+            docs: self.docs,
+            theme,
+            output: self.output,
+            pager: self.pager,
+            width: self.width,
+            show_hidden: self.show_hidden
+                || std::env::var_os("CHKC_HELP_SHOW_HIDDEN")
+                    .is_some_and(|v| !v.is_empty() && v != "0"),
+            show_env_values: self.show_env_values,
+            verbosity: self.verbosity,
+        }
+    }
+}
+
+impl HelpContext {
+    /// start a context around the root `clap::Command`.
+    pub fn builder(root: Command) -> HelpContextBuilder {
+        HelpContextBuilder {
+            app_name: None,
+            bin_name: None,
+            version: None,
+            root,
+            docs: DocRegistry::new(),
+            theme: None,
+            color: None,
+            output: OutputPolicy::default(),
+            pager: PagerPolicy::default(),
+            width: None,
+            show_hidden: false,
+            show_env_values: false,
+            verbosity: HelpVerbosity::default(),
+        }
+    }
+
+    /// name shown on the program page and in titles.
+    pub fn app_name(&self) -> &str {
+        &self.app_name
+    }
+
+    /// binary name used in usage lines and subcommand titles.
+    pub fn bin_name(&self) -> &str {
+        &self.bin_name
+    }
+
+    /// version shown on the program page, if any.
+    pub fn version(&self) -> Option<&str> {
+        self.version.as_deref()
+    }
+
+    /// the root command as it was passed in (not built).
+    pub fn root(&self) -> &Command {
+        &self.root
+    }
+
+    /// extra docs and guides.
+    pub fn docs(&self) -> &DocRegistry {
+        &self.docs
+    }
+
+    /// colors, with the builder's color override applied.
+    pub fn theme(&self) -> &HelpTheme {
+        &self.theme
+    }
+
+    /// whether pages scroll, print, or pick based on their height.
+    pub fn output(&self) -> OutputPolicy {
+        self.output
+    }
+
+    /// which pager long pages go through.
+    pub fn pager(&self) -> PagerPolicy {
+        self.pager
+    }
+
+    /// wrap width for plain text, if one was set.
+    pub fn width(&self) -> Option<usize> {
+        self.width
+    }
+
+    /// whether hidden subcommands and arguments are listed.
+    pub fn show_hidden(&self) -> bool {
+        self.show_hidden
+    }
+
+    /// whether env var values are printed, not just whether they're set.
+    pub fn show_env_values(&self) -> bool {
+        self.show_env_values
+    }
+
+    /// short or long help.
+    pub fn verbosity(&self) -> HelpVerbosity {
+        self.verbosity
+    }
+
+    /// handle the args of your `help` subcommand (a topic, or `--all`).
+    pub fn run(&self, args: &HelpArgs) -> Result<(), HelpError> {
+        if let Some(args) = self.flags_in_topic(args) {
+            return self.run(&args);
+        }
+
+        let verbosity = if args.short {
+            HelpVerbosity::Short
+        } else if args.long {
+            HelpVerbosity::Long
+        } else {
+            self.verbosity
+        };
+
+        if (args.hidden && !self.show_hidden) || verbosity != self.verbosity {
+            let mut context = self.clone();
+            context.show_hidden |= args.hidden;
+            context.verbosity = verbosity;
+            return context.run(args);
+        }
+
+        if args.all {
+            return self.run_manual();
+        }
+
+        self.run_topic(&args.topic)
+    }
+
+    /// `--short`, `--long` and `--hidden` typed after the topic end up in it, since the topic takes
+    /// hyphen values for argument pages (`help commit --message`). pull them back out, unless the
+    /// topic ends on a real argument of that name.
+    fn flags_in_topic(&self, args: &HelpArgs) -> Option<HelpArgs> {
+        let mut args = args.clone();
+        let segments = std::mem::take(&mut args.topic);
+        let mut found = false;
+
+        for (i, segment) in segments.iter().enumerate() {
+            let flag = match segment.as_str() {
+                "--short" => &mut args.short,
+                "--long" => &mut args.long,
+                "--hidden" => &mut args.hidden,
+                _ => {
+                    args.topic.push(segment.clone());
+                    continue;
+                }
+            };
+
+            let mut topic = args.topic.clone();
+            topic.push(segment.clone());
+            if i + 1 == segments.len()
+                && matches!(self.resolve(&topic), Ok(HelpTarget::Argument { .. }))
+            {
+                args.topic = topic;
+                continue;
+            }
+
+            *flag = true;
+            found = true;
+        }
+
+        // clap rejects `--short --long` in front of the topic; after it, the last one wins.
+        if args.short && args.long {
+            let short = segments.iter().rposition(|s| s == "--short");
+            let long = segments.iter().rposition(|s| s == "--long");
+            args.short = short > long;
+            args.long = !args.short;
+        }
+
+        found.then_some(args)
+    }
+
+    /// show program help, e.g. when no command was provided.
+    pub fn run_program(&self) -> Result<(), HelpError> {
+        self.run_topic(&[])
+    }
+
+    /// resolve a topic against the clap tree and docs, see [`resolve_help`].
+    pub fn resolve(&self, topic: &[String]) -> Result<HelpTarget<'_>, HelpError> {
+        resolve_help(&self.built, &self.docs, topic)
+    }
+
+    /// show whatever `topic` points at, printing unknown topics to stderr before returning them.
+    pub fn run_topic(&self, topic: &[String]) -> Result<(), HelpError> {
+        match self.markdown(topic) {
+            Ok(Markdown::Page(md)) => renderer::show(
+                &self.theme,
+                &self.app_name,
+                md,
+                self.output,
+                self.width,
+                self.pager,
+            ),
+            Ok(Markdown::Guide(md)) => renderer::show_guide(
+                &self.theme,
+                &self.app_name,
+                md,
+                self.output,
+                self.width,
+                self.pager,
+            ),
+            Err(err) => {
+                self.report(&err);
+                Err(err)
+            }
+        }
+    }
+
+    /// write whatever `topic` points at into `out`, laid out at `width` columns.
+    ///
+    /// nothing is printed to the terminal, not even for unknown topics.
+    pub fn write_topic<W: Write>(
+        &self,
+        out: &mut W,
+        topic: &[String],
+        width: usize,
+    ) -> Result<(), HelpError> {
+        match self.markdown(topic)? {
+            Markdown::Page(md) => renderer::write_markdown(
+                out,
+                &self.theme,
+                &renderer::page_skin(&self.theme),
+                &md,
+                width,
+            ),
+            Markdown::Guide(md) => {
+                renderer::write_markdown(out, &self.theme, &self.theme.skin, &md, width)
+            }
+        }
+    }
+
+    /// like [`HelpContext::write_topic`], into a `String`.
+    pub fn topic_string(&self, topic: &[String], width: usize) -> Result<String, HelpError> {
+        let mut out = Vec::new();
+        self.write_topic(&mut out, topic, width)?;
+        Ok(String::from_utf8_lossy(&out).into_owned())
+    }
+
+    /// render every (visible) command in the tree as one manual with a table of contents.
+    pub fn run_manual(&self) -> Result<(), HelpError> {
+        let md = self.manual_markdown();
+        renderer::show(
+            &self.theme,
+            &self.app_name,
+            md,
+            self.output,
+            self.width,
+            self.pager,
+        )
+    }
+
+    /// write the whole manual into `out`, laid out at `width` columns.
+    pub fn write_manual<W: Write>(&self, out: &mut W, width: usize) -> Result<(), HelpError> {
+        let skin = renderer::page_skin(&self.theme);
+        renderer::write_markdown(out, &self.theme, &skin, &self.manual_markdown(), width)
+    }
+
+    fn manual_markdown(&self) -> String {
+        let mut pages = vec![self
+            .page("", &self.built)
+            .with_docs(self.docs.command(""))
+            .with_guide_count(self.docs.guide_count())];
+
+        self.collect_pages(&mut pages, &self.built, &[]);
+
+        renderer::manual_markdown(&pages)
+    }
+
+    fn markdown(&self, topic: &[String]) -> Result<Markdown, HelpError> {
+        let docs = &self.docs;
+
+        let md = match self.resolve(topic)? {
+            HelpTarget::Command {
+                path,
+                cmd,
+                invoked_as,
+            } => {
+                let page = self
+                    .page(&path, cmd)
+                    .with_docs(docs.command(&path))
+                    .with_invoked_as(invoked_as);
+
+                renderer::page_markdown(&page, 1)
+            }
+            HelpTarget::Argument { path, cmd, arg } => {
+                let page = HelpArgumentPage::from_clap(&self.bin_name, &path, cmd, arg)
+                    .with_docs(docs.command(&relations::argument_key(&path, arg)))
+                    .with_show_env_values(self.show_env_values);
+
+                renderer::argument_markdown(&page)
+            }
+            HelpTarget::Guide { path, name } => return self.guide_markdown(&path, name),
+            HelpTarget::GuideIndex => {
+                let guides: Vec<HelpGuideEntry> = docs
+                    .guide_keys()
+                    .into_iter()
+                    .flat_map(|key| self.guide_entries(key, true))
+                    .collect();
+
+                renderer::guide_index_markdown(&self.app_name, &guides)
+            }
+            HelpTarget::Tree { depth } => {
+                let mut tree = tree_node(docs, &self.built, &[], depth, self.show_hidden);
+                tree.name = self.app_name.clone();
+                tree.has_guide = docs.has_guides(&self.guide_key(""));
+
+                renderer::tree_markdown(&self.app_name, &tree)
+            }
+            HelpTarget::Search { terms } => {
+                let hits = crate::search(&self.root, docs, &self.app_name, &terms);
+                renderer::search_markdown(&self.app_name, &terms, &hits)
+            }
+            HelpTarget::Program { cmd } => {
+                let page = self
+                    .page("", cmd)
+                    .with_docs(docs.command(""))
+                    .with_guide_count(docs.guide_count());
+
+                renderer::page_markdown(&page, 1)
+            }
+        };
+
+        Ok(Markdown::Page(md))
+    }
+
+    fn guide_markdown(&self, path: &str, name: Option<String>) -> Result<Markdown, HelpError> {
+        let docs = &self.docs;
+        let key = self.guide_key(path);
+        let named = self.guide_entries(&key, false);
+
+        match name {
+            Some(name) => match docs.named_guide(&key, &name) {
+                Some(guide) => Ok(Markdown::Guide(renderer::guide_markdown(guide, &[]))),
+                None => {
+                    let names = docs.guide_names(&key);
+                    let mut guide_path: Vec<String> = path
+                        .split('.')
+                        .filter(|s| !s.is_empty())
+                        .map(str::to_string)
+                        .collect();
+                    guide_path.push("guide".to_string());
+
+                    Err(UnknownTopic {
+                        path: guide_path,
+                        suggestions: did_you_mean(&name, names.iter().copied()),
+                        segment: name,
+                        children: names.iter().map(|n| n.to_string()).collect(),
+                    }
+                    .into())
+                }
+            },
+            None => match docs.guide(&key) {
+                Some(guide) => Ok(Markdown::Guide(renderer::guide_markdown(guide, &named))),
+                None if !named.is_empty() => Ok(Markdown::Page(renderer::guide_index_markdown(
+                    &self.app_name,
+                    &named,
+                ))),
+                None => Err(HelpError::GuideNotFound {
+                    path: if key.is_empty() {
+                        self.app_name.clone()
+                    } else {
+                        key
+                    },
+                }),
+            },
+        }
+    }
+
+    /// explain a failed topic on stderr.
+    fn report(&self, err: &HelpError) {
+        match err {
+            HelpError::UnknownTopic(unknown) => {
+                renderer::render_unknown_topic(&self.theme, unknown);
+            }
+            HelpError::GuideNotFound { path }
+                if !self.theme.use_color(std::io::stderr().is_terminal()) =>
+            {
+                eprintln!("Guide for {} was not found.", path);
+            }
+            HelpError::GuideNotFound { path } => {
+                eprintln!(
+                    "Guide for {} was {}.",
+                    path.as_str().with(self.theme.accent).bold(),
+                    "not found".red().bold()
+                );
+            }
+            _ => {}
+        }
+    }
+
+    /// the program page is titled with the app name, subcommands with the binary name.
+    ///
+    /// `cmd` comes from the built tree, so options inherited from `path`'s parents are split off.
+    fn page(&self, path: &str, cmd: &Command) -> HelpPage {
+        let name = if path.is_empty() {
+            &self.app_name
+        } else {
+            &self.bin_name
+        };
+
+        let mut page = HelpPage::from_clap(name, self.version.as_deref(), path, cmd);
+
+        let segments: Vec<&str> = path.split('.').filter(|s| !s.is_empty()).collect();
+        if let Some((_, parents)) = segments.split_last() {
+            let parent = parents
+                .iter()
+                .try_fold(&self.built, |cmd, name| cmd.find_subcommand(name));
+            if let Some(parent) = parent {
+                page = page.with_inherited(parent);
+            }
+        }
+
+        page.with_bin_name(&self.bin_name)
+            .with_show_hidden(self.show_hidden)
+            .with_show_env_values(self.show_env_values)
+            .with_verbosity(self.verbosity)
+    }
+
+    fn collect_pages(&self, pages: &mut Vec<HelpPage>, cmd: &Command, path: &[String]) {
+        for sc in cmd
+            .get_subcommands()
+            .filter(|sc| self.show_hidden || !sc.is_hide_set())
+        {
+            let mut sub_path = path.to_vec();
+            sub_path.push(sc.get_name().to_string());
+
+            let key = sub_path.join(".");
+            pages.push(self.page(&key, sc).with_docs(self.docs.command(&key)));
+
+            self.collect_pages(pages, sc, &sub_path);
+        }
+    }
+
+    /// registry key of the guides for a command path.
+    ///
+    /// the program guides are keyed by the app name, with `""` accepted like everywhere else.
+    fn guide_key(&self, path: &str) -> String {
+        if path.is_empty() && self.docs.has_guides(&self.app_name) {
+            self.app_name.clone()
+        } else {
+            path.to_string()
+        }
+    }
+
+    /// index entries for the guides under `key`, optionally including the default one.
+    fn guide_entries(&self, key: &str, with_default: bool) -> Vec<HelpGuideEntry> {
+        let docs = &self.docs;
+
+        // list the program guides as the root, whichever way they were keyed.
+        let path = if key == self.app_name { "" } else { key };
+
+        let default = docs
+            .guide(key)
+            .filter(|_| with_default)
+            .map(|guide| HelpGuideEntry::new(path, None, guide));
+
+        let named = docs.guide_names(key).into_iter().map(|name| {
+            HelpGuideEntry::new(
+                path,
+                Some(name),
+                docs.named_guide(key, name).unwrap_or_default(),
+            )
+        });
+
+        default.into_iter().chain(named).collect()
+    }
+}
+
+fn tree_node(
+    docs: &DocRegistry,
+    cmd: &Command,
+    path: &[String],
+    depth: Option<usize>,
+    show_hidden: bool,
+) -> HelpTreeNode {
+    let key = path.join(".");
+    let visible = cmd
+        .get_subcommands()
+        .filter(|sc| show_hidden || !sc.is_hide_set());
+
+    let (children, truncated) = if depth == Some(0) {
+        (Vec::new(), visible.count())
+    } else {
+        let children = visible
+            .map(|sc| {
+                let mut sub_path = path.to_vec();
+                sub_path.push(sc.get_name().to_string());
+                tree_node(docs, sc, &sub_path, depth.map(|d| d - 1), show_hidden)
+            })
+            .collect();
+        (children, 0)
+    };
+
+    HelpTreeNode {
+        name: cmd.get_name().to_string(),
+        summary: cmd.get_about().map(styled::to_markdown),
+        has_guide: docs.has_guides(&key),
+        has_docs: docs.command(&key).is_some(),
+        hidden: cmd.is_hide_set(),
+        children,
+        truncated,
+    }
+}
+
+/// `cmd` without the help / version flags and `help` subcommands clap generates while building,
+/// at every level. the pages describe those themselves.
+fn help_tree(mut cmd: Command) -> Command {
+    let names: Vec<String> = cmd
+        .get_subcommands()
+        .map(|sc| sc.get_name().to_string())
+        .collect();
+    for name in names {
+        cmd = cmd.mut_subcommand(name, help_tree);
+    }
+
+    cmd.disable_help_subcommand(true)
+        .disable_help_flag(true)
+        .disable_version_flag(true)
+}
+
+/// file name of the running executable, if the platform tells us.
+pub(crate) fn exe_name() -> Option<String> {
+    std::env::current_exe()
+        .ok()?
+        .file_name()?
+        .to_str()
+        .map(str::to_string)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::{Arg, ArgAction};
+
+    fn builder() -> HelpContextBuilder {
+        let root = Command::new("demo")
+            .about("a demo app")
+            .arg(
+                Arg::new("verbose")
+                    .short('v')
+                    .long("verbose")
+                    .global(true)
+                    .action(ArgAction::SetTrue)
+                    .help("be loud"),
+            )
+            .subcommand(
+                Command::new("commit")
+                    .about("record changes")
+                    .alias("ci")
+                    .arg(
+                        Arg::new("message")
+                            .short('m')
+                            .long("message")
+                            .env("CHKC_HELP_TEST_UNSET")
+                            .help("the message"),
+                    ),
+            )
+            .subcommand(Command::new("search").about("search the index"));
+
+        let mut docs = DocRegistry::new();
+        docs.register_guide("commit", "# committing\n\nwrite good messages.\n");
+
+        HelpContext::builder(root)
+            .bin_name("demo")
+            .docs(docs)
+            .color(ColorPolicy::Never)
+    }
+
+    fn context() -> HelpContext {
+        builder().build()
+    }
+
+    fn topic(context: &HelpContext, topic: &[&str]) -> Result<String, HelpError> {
+        let topic: Vec<String> = topic.iter().map(|t| t.to_string()).collect();
+        context.topic_string(&topic, 80)
+    }
+
+    #[test]
+    fn program_page_lists_commands_and_guides() {
+        let out = topic(&context(), &[]).unwrap();
+
+        assert!(out.starts_with("demo\na demo app\n"));
+        assert!(out.contains("Usage: demo [OPTIONS] [COMMAND]"));
+        assert!(out.contains("│commit │record changes  │"));
+        assert!(out.contains("1 guide available, list it with help guides"));
+    }
+
+    #[test]
+    fn aliases_open_the_command_they_name() {
+        let out = topic(&context(), &["ci"]).unwrap();
+
+        assert!(out.starts_with("demo commit\n(invoked as ci)\nrecord changes\n"));
+    }
+
+    #[test]
+    fn globals_are_listed_and_openable_from_subcommands() {
+        let context = context();
+
+        let commit = topic(&context, &["commit"]).unwrap();
+        assert!(commit.contains("Global options:\n• -v, --verbose: be loud\n"));
+
+        let verbose = topic(&context, &["commit", "-v"]).unwrap();
+        assert!(verbose.starts_with("demo commit --verbose\n(option)\nbe loud\n"));
+    }
+
+    #[test]
+    fn env_bindings_say_whether_they_are_set() {
+        // never set by any test, values are covered by the renderer's tests.
+        let context = builder().show_env_values(true).build();
+
+        let commit = topic(&context, &["commit"]).unwrap();
+        assert!(commit.contains("(env: CHKC_HELP_TEST_UNSET, not set)"));
+
+        let message = topic(&context, &["commit", "--message"]).unwrap();
+        assert!(message.contains("Environment: CHKC_HELP_TEST_UNSET (not set)"));
+    }
+
+    #[test]
+    fn real_subcommands_win_over_reserved_topics() {
+        let out = topic(&context(), &["search"]).unwrap();
+
+        assert!(out.starts_with("demo search\nsearch the index\n"));
+    }
+
+    #[test]
+    fn guides_and_the_guide_index() {
+        let context = context();
+
+        let guide = topic(&context, &["commit", "guide"]).unwrap();
+        assert!(guide.contains("committing"));
+        assert!(guide.contains("write good messages."));
+
+        let index = topic(&context, &["guides"]).unwrap();
+        assert!(index.starts_with("demo guides\n"));
+        assert!(index.contains("│help commit guide│committing│"));
+    }
+
+    #[test]
+    fn tree_keeps_the_indent_below_a_last_child() {
+        let root = Command::new("app").subcommand(
+            Command::new("remote")
+                .about("manage remotes")
+                .subcommand(
+                    Command::new("add")
+                        .about("add a remote")
+                        .subcommand(Command::new("deep").about("go deeper")),
+                )
+                .subcommand(Command::new("rm").about("remove a remote")),
+        );
+
+        for color in [ColorPolicy::Never, ColorPolicy::Always] {
+            let context = HelpContext::builder(root.clone())
+                .bin_name("app")
+                .color(color)
+                .build();
+            let out = topic(&context, &["tree"]).unwrap();
+            let rows: Vec<&str> = out.lines().skip(1).take(5).collect();
+
+            // no code block: the indent stays and nothing gets a background.
+            let nbsp = "\u{a0}".repeat(4);
+            assert_eq!(
+                rows,
+                vec![
+                    "app".to_string(),
+                    "└── remote        manage remotes".to_string(),
+                    format!("{}├── add       add a remote", nbsp),
+                    format!("{}│   └── deep  go deeper", nbsp),
+                    format!("{}└── rm        remove a remote", nbsp),
+                ]
+            );
+        }
+    }
+
+    #[test]
+    fn unknown_topics_suggest_close_matches() {
+        let Err(HelpError::UnknownTopic(err)) = topic(&context(), &["comit"]) else {
+            panic!("expected an unknown topic");
+        };
+
+        assert_eq!(err.segment, "comit");
+        assert_eq!(err.suggestions, vec!["commit"]);
+        assert_eq!(err.children, vec!["commit", "search", "--verbose"]);
+    }
+}
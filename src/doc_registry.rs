@@ -3,7 +3,7 @@
 //! keys use dot separators for subcommands (e.g. `"git.commit.amend"`). an empty key means "the
 //! program itself".
 
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 
 /// extra help metadata layered on top of clap output.
 #[derive(Debug, Clone)]
@@ -37,10 +37,11 @@ impl CommandDoc {
 }
 
 /// holds every command doc and guide for the current program session.
-#[derive(Default)]
+#[derive(Debug, Default, Clone)]
 pub struct DocRegistry {
     commands: HashMap<String, CommandDoc>,
     guides: HashMap<String, String>,
+    named_guides: HashMap<String, BTreeMap<String, String>>,
 }
 
 impl DocRegistry {
@@ -63,6 +64,21 @@ impl DocRegistry {
         self.guides.insert(key.into(), content.into());
     }
 
+    /// add a named guide under a command path, opened via `guide <name>` (e.g.
+    /// `help remote guide setup`). the unnamed guide from [`register_guide`](Self::register_guide)
+    /// stays the default one.
+    pub fn register_named_guide<K, N, C>(&mut self, key: K, name: N, content: C)
+    where
+        K: Into<String>,
+        N: Into<String>,
+        C: Into<String>,
+    {
+        self.named_guides
+            .entry(key.into())
+            .or_default()
+            .insert(name.into(), content.into());
+    }
+
     /// fetch docs for a command, if any.
     pub fn command(&self, key: &str) -> Option<&CommandDoc> {
         self.commands.get(key)
@@ -72,4 +88,43 @@ impl DocRegistry {
     pub fn guide(&self, key: &str) -> Option<&str> {
         self.guides.get(key).map(|s| s.as_str())
     }
+
+    /// fetch markdown for a named guide, if any.
+    pub fn named_guide(&self, key: &str, name: &str) -> Option<&str> {
+        self.named_guides
+            .get(key)
+            .and_then(|guides| guides.get(name))
+            .map(|s| s.as_str())
+    }
+
+    /// names of the guides registered under a command path, sorted.
+    pub fn guide_names(&self, key: &str) -> Vec<&str> {
+        self.named_guides
+            .get(key)
+            .map(|guides| guides.keys().map(String::as_str).collect())
+            .unwrap_or_default()
+    }
+
+    /// whether a command path has any guide, default or named.
+    pub fn has_guides(&self, key: &str) -> bool {
+        self.guides.contains_key(key) || self.named_guides.contains_key(key)
+    }
+
+    /// how many guides are registered in total, default and named.
+    pub fn guide_count(&self) -> usize {
+        self.guides.len() + self.named_guides.values().map(BTreeMap::len).sum::<usize>()
+    }
+
+    /// every key with a guide attached (default or named), sorted.
+    pub fn guide_keys(&self) -> Vec<&str> {
+        let mut keys: Vec<&str> = self
+            .guides
+            .keys()
+            .chain(self.named_guides.keys())
+            .map(String::as_str)
+            .collect();
+        keys.sort_unstable();
+        keys.dedup();
+        keys
+    }
 }
@@ -0,0 +1,98 @@
+//! errors surfaced by the help pipeline.
+//!
+//! everything public returns [`HelpError`] so callers can tell a typo from a missing guide or a
+//! broken terminal and pick their own exit codes.
+
+use std::{fmt, io};
+
+/// anything that can go wrong while resolving or showing help.
+#[derive(Debug)]
+pub enum HelpError {
+    /// the topic didn't resolve to a command, argument or guide.
+    UnknownTopic(UnknownTopic),
+
+    /// the topic pointed at a guide nobody registered.
+    GuideNotFound { path: String },
+
+    /// we needed the terminal (size, raw mode) and couldn't get it.
+    TerminalUnavailable(io::Error),
+
+    /// writing the output failed.
+    Io(io::Error),
+}
+
+impl fmt::Display for HelpError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            HelpError::UnknownTopic(err) => err.fmt(f),
+            HelpError::GuideNotFound { path } => write!(f, "guide for `{}` was not found", path),
+            HelpError::TerminalUnavailable(_) => write!(f, "terminal unavailable"),
+            HelpError::Io(_) => write!(f, "failed to write help"),
+        }
+    }
+}
+
+impl std::error::Error for HelpError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            HelpError::UnknownTopic(_) | HelpError::GuideNotFound { .. } => None,
+            HelpError::TerminalUnavailable(err) | HelpError::Io(err) => Some(err),
+        }
+    }
+}
+
+impl From<UnknownTopic> for HelpError {
+    fn from(err: UnknownTopic) -> Self {
+        HelpError::UnknownTopic(err)
+    }
+}
+
+impl From<io::Error> for HelpError {
+    fn from(err: io::Error) -> Self {
+        HelpError::Io(err)
+    }
+}
+
+impl From<termimad::Error> for HelpError {
+    fn from(err: termimad::Error) -> Self {
+        match err {
+            termimad::Error::IO(err) => HelpError::Io(err),
+            // the terminal is too narrow to lay the view out.
+            other => HelpError::TerminalUnavailable(io::Error::other(other)),
+        }
+    }
+}
+
+/// a topic segment didn't match anything at the level where resolution stopped.
+#[derive(Debug, Clone)]
+pub struct UnknownTopic {
+    /// segments that did resolve before the failure (empty for the program root).
+    pub path: Vec<String>,
+
+    /// the segment that didn't match.
+    pub segment: String,
+
+    /// close matches, best first.
+    pub suggestions: Vec<String>,
+
+    /// every valid topic at the level where resolution stopped.
+    pub children: Vec<String>,
+}
+
+impl fmt::Display for UnknownTopic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "unknown help topic `{}`", self.segment)?;
+
+        if !self.path.is_empty() {
+            write!(f, " under `{}`", self.path.join(" "))?;
+        }
+
+        if let Some(best) = self.suggestions.first() {
+            write!(f, ", did you mean `{}`?", best)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl std::error::Error for UnknownTopic {}
@@ -1,29 +1,86 @@
 //! helpers for wiring clap commands into nice terminal help output.
 
-use clap::{Args, Command};
-use termimad::crossterm::style::Stylize;
+use clap::{Arg, Args, Command};
 
-use crate::{DocRegistry, HelpPage, HelpTheme};
+use crate::context::HelpContext;
+use crate::error::{HelpError, UnknownTopic};
+use crate::relations;
+use crate::suggest::did_you_mean;
+use crate::{DocRegistry, HelpTheme};
 
 /// args for your help command.
 #[derive(Args, Debug, Clone)]
 pub struct HelpArgs {
     /// Command to print information about
+    #[arg(allow_hyphen_values = true)]
     pub topic: Vec<String>,
+
+    /// Print the help of every command as one manual
+    #[arg(long, conflicts_with = "topic")]
+    pub all: bool,
+
+    /// Only print one-line descriptions
+    #[arg(long, conflicts_with = "long")]
+    pub short: bool,
+
+    /// Print the full long help (the default)
+    #[arg(long)]
+    pub long: bool,
+
+    /// Also list hidden commands and arguments
+    #[arg(long, hide = true)]
+    pub hidden: bool,
 }
 
 /// resolved help target (a command path, a guide, or the program root).
 pub enum HelpTarget<'a> {
-    Command { path: String, cmd: &'a Command },
-    Guide { path: String },
+    /// `invoked_as` holds what the user typed when it differs from the canonical path (an alias,
+    /// a subcommand flag or an inferred prefix).
+    Command {
+        path: String,
+        cmd: &'a Command,
+        invoked_as: Option<String>,
+    },
+    /// a single argument of `cmd`, addressed as `--long`, `-s` or by positional name.
+    Argument {
+        path: String,
+        cmd: &'a Command,
+        arg: &'a Arg,
+    },
+    /// `name` picks one of the named guides, `None` means the default guide (or the listing).
+    Guide { path: String, name: Option<String> },
+    /// every registered guide (`app help guides`).
+    GuideIndex,
+    /// the subcommand hierarchy, optionally limited to `depth` levels (`app help tree 2`).
+    Tree { depth: Option<usize> },
+    /// full-text search over commands, arguments and guides (`app help search <terms>`).
+    Search { terms: Vec<String> },
     Program { cmd: &'a Command },
 }
 
 /// walk the clap tree to find the thing the user asked for.
 ///
 /// `guide` is special-cased so `app help foo bar guide` opens the `foo.bar` guide instead of looking
-/// for a `guide` subcommand.
-pub fn resolve_help<'a>(root: &'a Command, topic: &[String]) -> anyhow::Result<HelpTarget<'a>> {
+/// for a `guide` subcommand, and `app help foo bar guide setup` opens the guide named `setup`.
+///
+/// segments are matched the way clap's parser matches subcommands: by name or alias, by
+/// `short_flag` / `long_flag` (`-c`, `--commit`), and by unambiguous prefix when
+/// `infer_subcommands` is set.
+///
+/// `guides` on its own is reserved for the guide index, `tree` (with an optional depth) for the
+/// command tree, and a leading `search` turns the rest of the topic into search terms. a real
+/// subcommand (or alias) with one of those names wins over the reserved meaning.
+///
+/// a trailing `--long`, `-s` or positional name that isn't a subcommand opens the detail page for
+/// that argument instead (`app help commit --message`).
+///
+/// unknown segments fail with an [`UnknownTopic`] carrying close matches among the sibling
+/// subcommands (and `guide`, if one is registered at that level).
+pub fn resolve_help<'a>(
+    root: &'a Command,
+    docs: &DocRegistry,
+    topic: &[String],
+) -> Result<HelpTarget<'a>, HelpError> {
     let mut cmd = root;
     let mut path = Vec::new();
 
@@ -31,27 +88,183 @@ pub fn resolve_help<'a>(root: &'a Command, topic: &[String]) -> anyhow::Result<H
         return Ok(HelpTarget::Program { cmd });
     }
 
-    for segment in topic {
+    // the reserved topics only apply when the app has no subcommand of that name.
+    let reserved = |name: &str| topic[0] == name && root.find_subcommand(name).is_none();
+
+    if reserved("guides") && topic.len() == 1 {
+        return Ok(HelpTarget::GuideIndex);
+    }
+
+    if reserved("tree") && topic.len() <= 2 {
+        let depth = match topic.get(1) {
+            Some(depth) => match depth.parse() {
+                Ok(depth) => Some(depth),
+                Err(_) => {
+                    return Err(UnknownTopic {
+                        path: vec!["tree".to_string()],
+                        segment: depth.clone(),
+                        suggestions: Vec::new(),
+                        children: Vec::new(),
+                    }
+                    .into())
+                }
+            },
+            None => None,
+        };
+
+        return Ok(HelpTarget::Tree { depth });
+    }
+
+    if reserved("search") {
+        return Ok(HelpTarget::Search {
+            terms: topic[1..].to_vec(),
+        });
+    }
+
+    for (i, segment) in topic.iter().enumerate() {
         if segment == "guide" {
+            if let Some(extra) = topic.get(i + 2) {
+                let mut guide_path = path.clone();
+                guide_path.extend(topic[i..i + 2].iter().cloned());
+                return Err(UnknownTopic {
+                    path: guide_path,
+                    segment: extra.clone(),
+                    suggestions: Vec::new(),
+                    children: Vec::new(),
+                }
+                .into());
+            }
+
             return Ok(HelpTarget::Guide {
                 path: path.join("."),
+                name: topic.get(i + 1).cloned(),
             });
         }
 
-        cmd = cmd
-            .get_subcommands()
-            .find(|c| c.get_name() == segment)
-            .ok_or_else(|| anyhow::anyhow!("Unknown help topic"))?;
+        cmd = match find_subcommand(root, cmd, &path, segment) {
+            Some(sc) => sc,
+            None => match find_argument(cmd, segment) {
+                Some(arg) if i + 1 == topic.len() => {
+                    return Ok(HelpTarget::Argument {
+                        path: path.join("."),
+                        cmd,
+                        arg,
+                    });
+                }
+                _ => return Err(unknown_topic(cmd, docs, &path, segment).into()),
+            },
+        };
 
-        path.push(segment.clone());
+        path.push(cmd.get_name().to_string());
     }
 
+    let invoked_as = topic.join(" ");
+    let canonical = path.join(" ");
+
     Ok(HelpTarget::Command {
         path: path.join("."),
         cmd,
+        invoked_as: (invoked_as != canonical).then_some(invoked_as),
     })
 }
 
+/// let clap's own parser pick the subcommand so aliases, `short_flag` / `long_flag` and
+/// `infer_subcommands` behave exactly like they do on the real command line.
+///
+/// the probe starts from the root with the canonical `path` in front, so settings clap only
+/// propagates at build time (like `infer_subcommands`) apply to nested levels too.
+fn find_subcommand<'a>(
+    root: &Command,
+    cmd: &'a Command,
+    path: &[String],
+    segment: &str,
+) -> Option<&'a Command> {
+    if let Some(sc) = cmd.find_subcommand(segment) {
+        return Some(sc);
+    }
+
+    let matches = root
+        .clone()
+        .no_binary_name(true)
+        .ignore_errors(true)
+        .disable_help_flag(true)
+        .disable_version_flag(true)
+        .try_get_matches_from(path.iter().map(String::as_str).chain([segment]))
+        .ok()?;
+
+    let mut matches = &matches;
+    for _ in path {
+        matches = matches.subcommand()?.1;
+    }
+
+    cmd.find_subcommand(matches.subcommand_name()?)
+}
+
+/// match `--long` / `-s` (aliases included) against options, anything else against positionals.
+fn find_argument<'a>(cmd: &'a Command, segment: &str) -> Option<&'a Arg> {
+    if let Some(long) = segment.strip_prefix("--") {
+        return cmd.get_arguments().find(|a| {
+            a.get_long() == Some(long) || a.get_all_aliases().is_some_and(|al| al.contains(&long))
+        });
+    }
+
+    if let Some(short) = segment.strip_prefix('-') {
+        let mut chars = short.chars();
+        let (Some(short), None) = (chars.next(), chars.next()) else {
+            return None;
+        };
+
+        return cmd.get_arguments().find(|a| {
+            a.get_short() == Some(short)
+                || a.get_all_short_aliases()
+                    .is_some_and(|al| al.contains(&short))
+        });
+    }
+
+    cmd.get_positionals().find(|a| {
+        a.get_id() == segment
+            || a.get_value_names()
+                .is_some_and(|names| names.iter().any(|n| n.eq_ignore_ascii_case(segment)))
+    })
+}
+
+fn unknown_topic(
+    cmd: &Command,
+    docs: &DocRegistry,
+    path: &[String],
+    segment: &str,
+) -> UnknownTopic {
+    let mut children: Vec<String> = cmd
+        .get_subcommands()
+        .filter(|c| !c.is_hide_set())
+        .map(|c| c.get_name().to_string())
+        .collect();
+
+    if docs.has_guides(&path.join(".")) {
+        children.push("guide".to_string());
+    }
+
+    children.extend(
+        cmd.get_arguments()
+            .filter(|a| !a.is_hide_set())
+            .map(relations::argument_name),
+    );
+
+    let aliases: Vec<&str> = cmd
+        .get_subcommands()
+        .filter(|c| !c.is_hide_set())
+        .flat_map(|c| c.get_visible_aliases())
+        .collect();
+    let candidates = children.iter().map(String::as_str).chain(aliases);
+
+    UnknownTopic {
+        path: path.to_vec(),
+        segment: segment.to_string(),
+        suggestions: did_you_mean(segment, candidates),
+        children,
+    }
+}
+
 /// handle a `help <topic>` command without custom docs.
 pub fn help_command(
     app_name: &str,
@@ -59,8 +272,8 @@ pub fn help_command(
     root: &Command,
     theme: &HelpTheme,
     args: &HelpArgs,
-) -> anyhow::Result<()> {
-    run_help_topic(app_name, app_version, root, &DocRegistry::new(), theme, &args.topic)
+) -> Result<(), HelpError> {
+    help_command_docs(app_name, app_version, root, &DocRegistry::new(), theme, args)
 }
 
 /// like [`help_command`] but with an attached [`DocRegistry`].
@@ -71,8 +284,8 @@ pub fn help_command_docs(
     docs: &DocRegistry,
     theme: &HelpTheme,
     args: &HelpArgs,
-) -> anyhow::Result<()> {
-    run_help_topic(app_name, app_version, root, docs, theme, &args.topic)
+) -> Result<(), HelpError> {
+    context(app_name, app_version, root, docs, theme).run(args)
 }
 
 /// show program help when no command was provided.
@@ -81,8 +294,8 @@ pub fn help_command_program(
     app_version: Option<&str>,
     root: &Command,
     theme: &HelpTheme,
-) -> anyhow::Result<()> {
-    run_help_topic(app_name, app_version, root, &DocRegistry::new(), theme, &Vec::new())
+) -> Result<(), HelpError> {
+    run_help_topic(app_name, app_version, root, &DocRegistry::new(), theme, &[])
 }
 
 /// program help with attached docs.
@@ -92,11 +305,11 @@ pub fn help_command_program_docs(
     root: &Command,
     docs: &DocRegistry,
     theme: &HelpTheme,
-) -> anyhow::Result<()> {
-    run_help_topic(app_name, app_version, root, docs, theme, &Vec::new())
+) -> Result<(), HelpError> {
+    run_help_topic(app_name, app_version, root, docs, theme, &[])
 }
 
-/// shared execution path for all help entrypoints.
+/// one-off [`HelpContext::run_topic`].
 pub fn run_help_topic(
     app_name: &str,
     app_version: Option<&str>,
@@ -104,54 +317,36 @@ pub fn run_help_topic(
     docs: &DocRegistry,
     theme: &HelpTheme,
     topic: &[String],
-) -> anyhow::Result<()> {
-    let target = resolve_help(root, topic)?;
-
-    match target {
-        HelpTarget::Command { path, cmd } => {
-            let page = HelpPage::from_clap(
-                std::env::current_exe()
-                    .expect("Failed to get executable path")
-                    .file_name()
-                    .expect("Failed to get executable name")
-                    .to_str()
-                    .unwrap(),
-                app_version,
-                &path,
-                cmd,
-            )
-            .with_docs(docs.command(&path));
-
-            crate::render_command_help(theme, &page);
-        }
-        HelpTarget::Guide { path } => {
-            let path = if path.is_empty() {
-                app_name.to_string()
-            } else {
-                path
-            };
-            if let Some(guide) = docs.guide(&path) {
-                let (_, rows) = termimad::crossterm::terminal::size().unwrap();
-                if guide.lines().count() > rows.into() {
-                    crate::run_scrollable_help(theme, app_name, guide.to_string())?;
-                } else {
-                    println!("{}", theme.skin.term_text(&guide));
-                }
-            } else {
-                println!(
-                    "Guide for {} was {}.",
-                    &path.with(theme.accent).bold(),
-                    "not found".red().bold()
-                )
-            }
-        }
-        HelpTarget::Program { cmd } => {
-            let page = HelpPage::from_clap(app_name, app_version, "", cmd)
-                .with_docs(docs.command(""));
+) -> Result<(), HelpError> {
+    context(app_name, app_version, root, docs, theme).run_topic(topic)
+}
 
-            crate::render_command_help(theme, &page);
-        }
+/// one-off [`HelpContext::run_manual`].
+pub fn run_help_manual(
+    app_name: &str,
+    app_version: Option<&str>,
+    root: &Command,
+    docs: &DocRegistry,
+    theme: &HelpTheme,
+) -> Result<(), HelpError> {
+    context(app_name, app_version, root, docs, theme).run_manual()
+}
+
+fn context(
+    app_name: &str,
+    app_version: Option<&str>,
+    root: &Command,
+    docs: &DocRegistry,
+    theme: &HelpTheme,
+) -> HelpContext {
+    let mut builder = HelpContext::builder(root.clone())
+        .app_name(app_name)
+        .docs(docs.clone())
+        .theme(theme.clone());
+
+    if let Some(version) = app_version {
+        builder = builder.version(version);
     }
 
-    Ok(())
+    builder.build()
 }
@@ -1,8 +1,14 @@
 //! typed snapshot of clap data that the renderer can turn into markdown.
 
+use std::any::TypeId;
+use std::path::PathBuf;
+
 use clap::builder::OsStr;
+use clap::ValueHint;
 
 use crate::doc_registry::CommandDoc;
+use crate::relations::{self, Relations};
+use crate::styled;
 
 /// everything we need to print help for a command path.
 #[derive(Debug, Clone)]
@@ -13,15 +19,33 @@ pub struct HelpPage {
     /// Application version (from clap metadata).
     pub version: Option<String>,
 
+    /// Binary name used in the usage line (e.g. "git")
+    pub bin_name: String,
+
     /// Full command path (e.g. "commit main")
     pub path: String,
 
+    /// What the user typed when it wasn't the canonical path (e.g. an alias like "ci")
+    pub invoked_as: Option<String>,
+
     /// One-line summary (from clap)
     pub summary: Option<String>,
 
     /// Optional longer description
     pub description: Option<String>,
 
+    /// Text printed before the help (clap's `before_help`)
+    pub before_help: Option<String>,
+
+    /// Prologue for long help (clap's `before_long_help`)
+    pub before_long_help: Option<String>,
+
+    /// Text printed after the help (clap's `after_help`)
+    pub after_help: Option<String>,
+
+    /// Epilogue for long help (clap's `after_long_help`)
+    pub after_long_help: Option<String>,
+
     /// Usage string (from clap)
     pub usage: String,
 
@@ -31,6 +55,9 @@ pub struct HelpPage {
     /// Flags and options
     pub options: Vec<HelpOption>,
 
+    /// Global options inherited from parent commands
+    pub globals: Vec<HelpOption>,
+
     /// Subcommands (for category-level help)
     pub subcommands: Vec<HelpSubcommand>,
 
@@ -39,16 +66,107 @@ pub struct HelpPage {
 
     /// Notes / tips / caveats
     pub notes: Vec<String>,
+
+    /// Number of registered guides (only set on the program page)
+    pub guide_count: usize,
+
+    /// Argument groups that constrain usage (exclusive or required ones)
+    pub groups: Vec<HelpGroup>,
+
+    /// List hidden subcommands and arguments in their own section
+    pub show_hidden: bool,
+
+    /// Print the current value of env vars, not just whether they're set
+    pub show_env_values: bool,
+
+    /// Terse one-liners or the full long help
+    pub verbosity: HelpVerbosity,
+}
+
+/// how much of the help text to print, like clap's `-h` vs `--help`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum HelpVerbosity {
+    /// one-line summaries (`about`, `help`), no long description or notes.
+    Short,
+
+    /// `long_about`, `long_help` and every note from the [`CommandDoc`].
+    #[default]
+    Long,
 }
 
 /// a flag/option with optional value and default info.
 #[derive(Debug, Clone)]
 pub struct HelpOption {
+    /// Clap id of the argument
+    pub id: String,
+
     pub short: Option<char>,
     pub long: Option<String>,
-    pub value: Option<String>,
+
+    /// Visible short aliases (`visible_short_alias`)
+    pub short_aliases: Vec<char>,
+
+    /// Visible long aliases (`visible_alias`)
+    pub aliases: Vec<String>,
+
+    /// What the option takes, `None` for plain flags
+    pub value: Option<HelpValue>,
     pub description: String,
+
+    /// Long help (clap's `long_help`), if it has one
+    pub long_description: Option<String>,
+
     pub default: String,
+    pub hidden: bool,
+    pub required: bool,
+
+    /// Arguments that can't be combined with this one
+    pub conflicts: Vec<String>,
+
+    /// Arguments that must be passed alongside this one
+    pub requires: Vec<String>,
+
+    /// Ids of the [`HelpGroup`]s this option belongs to
+    pub groups: Vec<String>,
+
+    /// Accepted values, if clap knows them (`ValueEnum`, `PossibleValuesParser`)
+    pub possible_values: Vec<HelpPossibleValue>,
+
+    /// Environment variable the option reads from
+    pub env: Option<HelpEnvVar>,
+
+    /// Section the option is listed under (clap's `help_heading`), `None` for "Options"
+    pub heading: Option<String>,
+}
+
+impl HelpOption {
+    /// snapshot of `arg`, a flag or option of `cmd`.
+    pub(crate) fn from_arg(cmd: &clap::Command, arg: &clap::Arg, relations: &Relations) -> Self {
+        Self {
+            id: arg.get_id().to_string(),
+            short: arg.get_short(),
+            long: arg.get_long().map(str::to_string),
+            short_aliases: arg.get_visible_short_aliases().unwrap_or_default(),
+            aliases: arg
+                .get_visible_aliases()
+                .unwrap_or_default()
+                .into_iter()
+                .map(str::to_string)
+                .collect(),
+            value: HelpValue::from_arg(arg),
+            description: arg.get_help().map(styled::to_markdown).unwrap_or_default(),
+            long_description: arg.get_long_help().map(styled::to_markdown),
+            default: default_value(arg),
+            hidden: arg.is_hide_set(),
+            required: arg.is_required_set(),
+            conflicts: relations.conflicts(arg),
+            requires: relations.requires(arg),
+            groups: group_ids(cmd, arg),
+            possible_values: HelpPossibleValue::from_arg(arg),
+            env: HelpEnvVar::from_arg(arg),
+            heading: arg.get_help_heading().map(str::to_string),
+        }
+    }
 }
 
 /// positional argument.
@@ -56,81 +174,585 @@ pub struct HelpOption {
 pub struct HelpArg {
     pub name: String,
     pub description: Option<String>,
+
+    /// Long help (clap's `long_help`), if it has one
+    pub long_description: Option<String>,
+
     pub required: bool,
     pub multiple: bool,
+    pub hidden: bool,
+
+    /// Shape of the value (count, delimiter, hint)
+    pub value: Option<HelpValue>,
+
+    /// Arguments that can't be combined with this one
+    pub conflicts: Vec<String>,
+
+    /// Arguments that must be passed alongside this one
+    pub requires: Vec<String>,
+
+    /// Ids of the [`HelpGroup`]s this argument belongs to
+    pub groups: Vec<String>,
+
+    /// Accepted values, if clap knows them
+    pub possible_values: Vec<HelpPossibleValue>,
+
+    /// Environment variable the argument reads from
+    pub env: Option<HelpEnvVar>,
+}
+
+/// a clap `ArgGroup` worth mentioning: mutually exclusive, required, or both.
+#[derive(Debug, Clone)]
+pub struct HelpGroup {
+    pub id: String,
+
+    /// Members as written on the command line (e.g. "--json")
+    pub args: Vec<String>,
+
+    /// At least one member has to be passed
+    pub required: bool,
+
+    /// Members may be combined (otherwise at most one is allowed)
+    pub multiple: bool,
+}
+
+impl HelpGroup {
+    /// the groups of `cmd` that constrain usage, with their visible members.
+    ///
+    /// the catch-all groups clap's derive adds for every struct (multiple, optional) are skipped.
+    pub fn from_clap(cmd: &clap::Command) -> Vec<Self> {
+        cmd.get_groups()
+            .filter_map(|group| {
+                // `is_multiple` wants `&mut self`.
+                let multiple = group.clone().is_multiple();
+                let required = group.is_required_set();
+                if multiple && !required {
+                    return None;
+                }
+
+                let args: Vec<String> = group
+                    .get_args()
+                    .filter_map(|id| cmd.get_arguments().find(|a| a.get_id() == id))
+                    .filter(|a| !a.is_hide_set())
+                    .map(relations::display_name)
+                    .collect();
+
+                (!args.is_empty()).then(|| Self {
+                    id: group.get_id().to_string(),
+                    args,
+                    required,
+                    multiple,
+                })
+            })
+            .collect()
+    }
+
+    /// `[--a | --b]`, or `(--a | --b)` when one of them is required.
+    pub fn synopsis(&self) -> String {
+        let inner = self.args.join(" | ");
+
+        if self.required {
+            format!("({})", inner)
+        } else {
+            format!("[{}]", inner)
+        }
+    }
+}
+
+/// the default value(s) worth printing: none under `hide_default_value`, and none for flags
+/// (built ones carry clap's implicit `false`).
+fn default_value(arg: &clap::Arg) -> String {
+    if arg.is_hide_default_value_set() || !arg.get_action().takes_values() {
+        return String::new();
+    }
+
+    arg.get_default_values()
+        .join(&OsStr::from(", "))
+        .to_str()
+        .unwrap_or_default()
+        .to_string()
+}
+
+/// ids of the groups `arg` belongs to.
+fn group_ids(cmd: &clap::Command, arg: &clap::Arg) -> Vec<String> {
+    cmd.get_groups()
+        .filter(|g| g.get_args().any(|id| id == arg.get_id()))
+        .map(|g| g.get_id().to_string())
+        .collect()
+}
+
+/// an environment variable an argument falls back to (`Arg::env`).
+#[derive(Debug, Clone)]
+pub struct HelpEnvVar {
+    pub name: String,
+
+    /// Whether it's set in the current process
+    pub set: bool,
+
+    /// Its current value, unless unset or hidden with `hide_env_values`. only printed when the page
+    /// opts in with `with_show_env_values`
+    pub value: Option<String>,
+}
+
+impl HelpEnvVar {
+    /// the env binding of `arg`, unless it has none or it's hidden with `hide_env`.
+    pub fn from_arg(arg: &clap::Arg) -> Option<Self> {
+        let name = arg.get_env().filter(|_| !arg.is_hide_env_set())?;
+        let value = std::env::var_os(name);
+
+        Some(Self {
+            name: name.to_string_lossy().into_owned(),
+            set: value.is_some(),
+            value: value
+                .filter(|_| !arg.is_hide_env_values_set())
+                .map(|v| v.to_string_lossy().into_owned()),
+        })
+    }
+}
+
+/// what an argument's value looks like: placeholders, how many, and what kind.
+#[derive(Debug, Clone)]
+pub struct HelpValue {
+    /// Placeholders, one per value name (e.g. ["KEY", "VALUE"])
+    pub names: Vec<String>,
+
+    /// Fewest values per occurrence (clap's `num_args`)
+    pub min: usize,
+
+    /// Most values per occurrence, `None` when unbounded
+    pub max: Option<usize>,
+
+    /// Character splitting one argument into several values (`value_delimiter`)
+    pub delimiter: Option<char>,
+
+    /// Kind of value, from the `ValueHint` or the parser type (e.g. "path", "url", "integer")
+    pub hint: Option<String>,
+}
+
+impl HelpValue {
+    /// the value shape of `arg`, `None` if it takes no values.
+    pub fn from_arg(arg: &clap::Arg) -> Option<Self> {
+        if !arg.get_action().takes_values() {
+            return None;
+        }
+
+        let names = match arg.get_value_names() {
+            Some(names) => names.iter().map(|n| n.to_string()).collect(),
+            None => vec![arg.get_id().to_string().to_uppercase()],
+        };
+        // unbuilt commands only know `num_args` when it was set explicitly.
+        let (min, max) = match arg.get_num_args() {
+            Some(range) => (
+                range.min_values(),
+                Some(range.max_values()).filter(|&max| max != usize::MAX),
+            ),
+            None => (1, Some(1)),
+        };
+
+        Some(Self {
+            names,
+            min,
+            max,
+            delimiter: arg.get_value_delimiter(),
+            hint: value_hint(arg),
+        })
+    }
+
+    /// clap-style placeholder: `<KEY> <VALUE>`, `<FILE>...`, `<ITEM>[,...]` or `[<NAME>]`.
+    pub fn placeholder(&self) -> String {
+        let names = match self.names.as_slice() {
+            // `num_args(2)` with a single name repeats it, like clap.
+            [name] if self.max == Some(self.min) && 1 < self.min => vec![name.clone(); self.min],
+            names => names.to_vec(),
+        };
+        let shown = names.len().max(1);
+
+        let mut placeholder = names
+            .iter()
+            .map(|n| format!("<{}>", n))
+            .collect::<Vec<_>>()
+            .join(" ");
+
+        match self.delimiter {
+            Some(delimiter) if shown == 1 => placeholder.push_str(&format!("[{}...]", delimiter)),
+            _ if self.max.is_none_or(|max| shown < max) => placeholder.push_str("..."),
+            _ => {}
+        }
+
+        if self.min == 0 {
+            placeholder = format!("[{}]", placeholder);
+        }
+
+        placeholder
+    }
+
+    /// value count the placeholder can't express on its own (e.g. "1..=10 values").
+    pub fn count(&self) -> Option<String> {
+        match self.max {
+            // `[<NAME>]` already says zero or one.
+            Some(1) if self.min == 0 => None,
+            Some(max) if max != self.min => Some(format!("{}..={} values", self.min, max)),
+            None if 1 < self.min => Some(format!("{}.. values", self.min)),
+            _ => None,
+        }
+    }
+}
+
+/// "path", "url" and friends from the value hint, or a word for the parser's type.
+fn value_hint(arg: &clap::Arg) -> Option<String> {
+    let hint = match arg.get_value_hint() {
+        ValueHint::AnyPath => "path",
+        ValueHint::FilePath => "file",
+        ValueHint::DirPath => "directory",
+        ValueHint::ExecutablePath => "executable",
+        ValueHint::CommandName => "command",
+        ValueHint::CommandString | ValueHint::CommandWithArguments => "command line",
+        ValueHint::Username => "user name",
+        ValueHint::Hostname => "hostname",
+        ValueHint::Url => "url",
+        ValueHint::EmailAddress => "email",
+        _ => {
+            // enums already list their values.
+            if !arg.get_possible_values().is_empty() {
+                return None;
+            }
+
+            let ty = arg.get_value_parser().type_id();
+            let is = |ids: &[TypeId]| ids.iter().any(|id| ty == *id);
+
+            if is(&[TypeId::of::<PathBuf>()]) {
+                "path"
+            } else if is(&[
+                TypeId::of::<i8>(),
+                TypeId::of::<i16>(),
+                TypeId::of::<i32>(),
+                TypeId::of::<i64>(),
+                TypeId::of::<u8>(),
+                TypeId::of::<u16>(),
+                TypeId::of::<u32>(),
+                TypeId::of::<u64>(),
+                TypeId::of::<usize>(),
+                TypeId::of::<isize>(),
+            ]) {
+                "integer"
+            } else if is(&[TypeId::of::<f32>(), TypeId::of::<f64>()]) {
+                "number"
+            } else {
+                return None;
+            }
+        }
+    };
+
+    Some(hint.to_string())
+}
+
+/// one accepted value of an argument.
+#[derive(Debug, Clone)]
+pub struct HelpPossibleValue {
+    pub name: String,
+    pub help: Option<String>,
+    pub hidden: bool,
+}
+
+impl HelpPossibleValue {
+    /// every value clap knows for `arg`, hidden ones flagged (none if `hide_possible_values`).
+    pub fn from_arg(arg: &clap::Arg) -> Vec<Self> {
+        // plain flags still carry `BoolValueParser`'s true/false.
+        if arg.is_hide_possible_values_set() || !arg.get_action().takes_values() {
+            return Vec::new();
+        }
+
+        arg.get_possible_values()
+            .iter()
+            .map(|v| Self {
+                name: v.get_name().to_string(),
+                help: v.get_help().map(styled::to_markdown),
+                hidden: v.is_hide_set(),
+            })
+            .collect()
+    }
 }
 
 /// child command for category-level help.
 #[derive(Debug, Clone)]
 pub struct HelpSubcommand {
     pub name: String,
+
+    /// Visible aliases and subcommand flags (e.g. "ci", "-C", "--commit")
+    pub aliases: Vec<String>,
+
+    pub summary: Option<String>,
+    pub hidden: bool,
+}
+
+/// the other visible ways to invoke `sc`: aliases, then its short and long flags.
+fn subcommand_aliases(sc: &clap::Command) -> Vec<String> {
+    let mut aliases: Vec<String> = sc.get_visible_aliases().map(str::to_string).collect();
+
+    aliases.extend(
+        sc.get_short_flag()
+            .into_iter()
+            .chain(sc.get_visible_short_flag_aliases())
+            .map(|c| format!("-{}", c)),
+    );
+    aliases.extend(
+        sc.get_long_flag()
+            .into_iter()
+            .chain(sc.get_visible_long_flag_aliases())
+            .map(|l| format!("--{}", l)),
+    );
+
+    aliases
+}
+
+/// a node of the command tree overview (`help tree`).
+#[derive(Debug, Clone)]
+pub struct HelpTreeNode {
+    pub name: String,
+    pub summary: Option<String>,
+
+    /// A guide is registered for this command path
+    pub has_guide: bool,
+
+    /// A [`CommandDoc`] is registered for this command path
+    pub has_docs: bool,
+
+    /// Hidden from regular help (only listed in show-hidden mode)
+    pub hidden: bool,
+
+    pub children: Vec<HelpTreeNode>,
+
+    /// Visible subcommands left out because of the depth limit
+    pub truncated: usize,
+}
+
+/// one row of the guide index (`help guides`).
+#[derive(Debug, Clone)]
+pub struct HelpGuideEntry {
+    /// Command path the guide hangs off (dot-separated, `""` for the program)
+    pub path: String,
+
+    /// Guide name, `None` for the default guide of the path
+    pub name: Option<String>,
+
+    /// First heading of the guide, if it starts with one
+    pub title: Option<String>,
+}
+
+impl HelpGuideEntry {
+    /// index entry for a guide, titled after its first markdown heading.
+    pub fn new(path: &str, name: Option<&str>, content: &str) -> Self {
+        let title = content
+            .lines()
+            .find(|l| !l.trim().is_empty())
+            .and_then(|l| l.trim().strip_prefix('#'))
+            .map(|l| l.trim_start_matches('#').trim().to_string())
+            .filter(|l| !l.is_empty());
+
+        Self {
+            path: path.to_string(),
+            name: name.map(str::to_string),
+            title,
+        }
+    }
+
+    /// the help topic that opens this guide (e.g. `help remote guide setup`).
+    pub fn topic(&self) -> String {
+        let mut topic = vec!["help".to_string()];
+        topic.extend(
+            self.path
+                .split('.')
+                .filter(|s| !s.is_empty())
+                .map(str::to_string),
+        );
+        topic.push("guide".to_string());
+        topic.extend(self.name.clone());
+        topic.join(" ")
+    }
+}
+
+/// detail page for a single argument (`help commit --message`).
+#[derive(Debug, Clone)]
+pub struct HelpArgumentPage {
+    /// Name of the binary / application (e.g. "git")
+    pub app_name: String,
+
+    /// Path of the command owning the argument (e.g. "commit")
+    pub path: String,
+
+    /// How the argument is written (e.g. "--message", "-m" or "<FILES>")
+    pub name: String,
+
+    pub short: Option<char>,
+    pub long: Option<String>,
+
+    /// Visible short aliases (`visible_short_alias`)
+    pub short_aliases: Vec<char>,
+
+    /// Visible long aliases (`visible_alias`)
+    pub aliases: Vec<String>,
+
+    pub positional: bool,
+    pub required: bool,
+
+    /// One-line help (from clap)
     pub summary: Option<String>,
+
+    /// Long help (from clap or a [`CommandDoc`])
+    pub description: Option<String>,
+
+    /// What the argument takes, `None` for plain flags
+    pub value: Option<HelpValue>,
+
+    /// Accepted values, if clap knows them
+    pub possible_values: Vec<HelpPossibleValue>,
+
+    pub default: String,
+
+    /// Environment variable the argument reads from
+    pub env: Option<HelpEnvVar>,
+
+    /// Arguments that can't be combined with this one
+    pub conflicts: Vec<String>,
+
+    /// Arguments that must be passed alongside this one
+    pub requires: Vec<String>,
+
+    /// Examples
+    pub examples: Vec<String>,
+
+    /// Notes / tips / caveats
+    pub notes: Vec<String>,
+
+    /// Print the current value of the env var, not just whether it's set
+    pub show_env_values: bool,
+}
+
+impl HelpArgumentPage {
+    /// build a detail page for `arg`, which must belong to `cmd`.
+    pub fn from_clap(app_name: &str, path: &str, cmd: &clap::Command, arg: &clap::Arg) -> Self {
+        let relations = Relations::new(cmd);
+
+        Self {
+            app_name: app_name.to_string(),
+            path: path.to_string(),
+            name: relations::display_name(arg),
+            short: arg.get_short(),
+            long: arg.get_long().map(str::to_string),
+            short_aliases: arg.get_visible_short_aliases().unwrap_or_default(),
+            aliases: arg
+                .get_visible_aliases()
+                .unwrap_or_default()
+                .into_iter()
+                .map(str::to_string)
+                .collect(),
+            positional: arg.is_positional(),
+            required: arg.is_required_set(),
+            summary: arg.get_help().map(styled::to_markdown),
+            description: arg.get_long_help().map(styled::to_markdown),
+            value: HelpValue::from_arg(arg),
+            possible_values: HelpPossibleValue::from_arg(arg),
+            default: default_value(arg),
+            env: HelpEnvVar::from_arg(arg),
+            conflicts: relations.conflicts(arg),
+            requires: relations.requires(arg),
+            examples: Vec::new(),
+            notes: Vec::new(),
+            show_env_values: false,
+        }
+    }
+
+    /// merge data from a [`CommandDoc`], falling back to clap metadata when missing.
+    pub fn with_docs(mut self, doc: Option<&CommandDoc>) -> Self {
+        if let Some(doc) = doc {
+            self.description = doc.description.clone().or(self.description);
+            self.examples = doc.examples.clone();
+            self.notes = doc.notes.clone();
+        }
+        self
+    }
+
+    /// print the env var's current value instead of only whether it's set.
+    pub fn with_show_env_values(mut self, show_env_values: bool) -> Self {
+        self.show_env_values = show_env_values;
+        self
+    }
 }
 
 impl HelpPage {
     /// build a page straight from a `clap::Command`.
+    ///
+    /// a command from a built tree (`Command::build` on the root) gets the full usage line and
+    /// its inherited globals, which [`HelpPage::with_inherited`] splits off.
     pub fn from_clap(
         app_name: &str,
         version: Option<&str>,
         path: &str,
         cmd: &clap::Command,
     ) -> Self {
+        let relations = Relations::new(cmd);
+
         let positionals = cmd
             .get_positionals()
             .map(|arg| HelpArg {
                 name: arg.get_id().to_string(),
-                description: arg.get_help().map(|s| s.to_string()),
+                description: arg.get_help().map(styled::to_markdown),
+                long_description: arg.get_long_help().map(styled::to_markdown),
                 required: arg.is_required_set(),
                 multiple: arg
                     .get_num_args()
                     .map(|n| n.min_values() != n.max_values() || 1 < n.min_values())
                     .unwrap_or_default(),
+                hidden: arg.is_hide_set(),
+                value: HelpValue::from_arg(arg),
+                conflicts: relations.conflicts(arg),
+                requires: relations.requires(arg),
+                groups: group_ids(cmd, arg),
+                possible_values: HelpPossibleValue::from_arg(arg),
+                env: HelpEnvVar::from_arg(arg),
             })
             .collect();
 
         let options = cmd
             .get_arguments()
             .filter(|a| !a.is_positional())
-            .map(|arg| HelpOption {
-                short: arg.get_short(),
-                long: arg.get_long().map(str::to_string),
-                value: if arg.get_action().takes_values() {
-                    arg.get_value_names()
-                        .and_then(|v| v.first())
-                        .map(|v| v.to_string())
-                } else {
-                    None
-                },
-                description: arg.get_help().unwrap_or_default().to_string(),
-                default: arg
-                    .get_default_values()
-                    .join(&OsStr::from(", "))
-                    .to_str()
-                    .unwrap_or_default()
-                    .to_string(),
-            })
+            .map(|arg| HelpOption::from_arg(cmd, arg, &relations))
             .collect();
 
         let subcommands = cmd
             .get_subcommands()
             .map(|sc| HelpSubcommand {
                 name: sc.get_name().to_string(),
-                summary: sc.get_about().map(|s| s.to_string()),
+                aliases: subcommand_aliases(sc),
+                summary: sc.get_about().map(styled::to_markdown),
+                hidden: sc.is_hide_set(),
             })
             .collect();
 
         Self {
             app_name: app_name.to_string(),
             version: version.map(|s| s.to_string()),
+            bin_name: cmd.get_bin_name().unwrap_or(app_name).to_string(),
             path: path.to_string(),
-            summary: cmd.get_about().map(|s| s.to_string()),
-            description: cmd.get_long_about().map(|s| s.to_string()),
-            usage: cmd.clone().render_usage().to_string(),
+            invoked_as: None,
+            summary: cmd.get_about().map(styled::to_markdown),
+            description: cmd.get_long_about().map(styled::to_markdown),
+            before_help: cmd.get_before_help().map(styled::to_markdown),
+            before_long_help: cmd.get_before_long_help().map(styled::to_markdown),
+            after_help: cmd.get_after_help().map(styled::to_markdown),
+            after_long_help: cmd.get_after_long_help().map(styled::to_markdown),
+            usage: relations::standalone(cmd).render_usage().to_string(),
             positionals,
             options,
+            globals: Vec::new(),
             subcommands,
             examples: Vec::new(),
             notes: Vec::new(),
+            guide_count: 0,
+            groups: HelpGroup::from_clap(cmd),
+            show_hidden: false,
+            show_env_values: false,
+            verbosity: HelpVerbosity::default(),
         }
     }
 
@@ -143,4 +765,216 @@ impl HelpPage {
         }
         self
     }
+
+    /// move the global options `parent` hands down into [`HelpPage::globals`].
+    ///
+    /// only meaningful on a built tree (`Command::build`), where clap has copied them into the
+    /// subcommand already.
+    pub fn with_inherited(mut self, parent: &clap::Command) -> Self {
+        let inherited = |opt: &HelpOption| {
+            parent
+                .get_arguments()
+                .any(|a| a.is_global_set() && a.get_id() == opt.id.as_str())
+        };
+
+        let (globals, options) = self.options.into_iter().partition(inherited);
+        self.globals = globals;
+        self.options = options;
+        self
+    }
+
+    /// mention that `count` guides can be listed with `help guides`.
+    pub fn with_guide_count(mut self, count: usize) -> Self {
+        self.guide_count = count;
+        self
+    }
+
+    /// also list hidden subcommands and arguments, in a separate section.
+    pub fn with_show_hidden(mut self, show_hidden: bool) -> Self {
+        self.show_hidden = show_hidden;
+        self
+    }
+
+    /// print env vars' current values instead of only whether they're set. off by default, since
+    /// help ends up in logs and the values may be secrets.
+    pub fn with_show_env_values(mut self, show_env_values: bool) -> Self {
+        self.show_env_values = show_env_values;
+        self
+    }
+
+    /// print terse one-liners or the full long help.
+    pub fn with_verbosity(mut self, verbosity: HelpVerbosity) -> Self {
+        self.verbosity = verbosity;
+        self
+    }
+
+    /// binary name to prefix the usage line with.
+    pub fn with_bin_name(mut self, bin_name: &str) -> Self {
+        self.bin_name = bin_name.to_string();
+        self
+    }
+
+    /// remember the alias / prefix the page was reached through.
+    pub fn with_invoked_as(mut self, invoked_as: Option<String>) -> Self {
+        self.invoked_as = invoked_as;
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::{Arg, ArgAction, Command};
+
+    fn value(arg: Arg) -> HelpValue {
+        HelpValue::from_arg(&arg).expect("takes a value")
+    }
+
+    fn shape(arg: Arg) -> (String, Option<String>) {
+        let value = value(arg);
+        (value.placeholder(), value.count())
+    }
+
+    #[test]
+    fn flags_have_no_value() {
+        let flag = Arg::new("force").long("force").action(ArgAction::SetTrue);
+        assert!(HelpValue::from_arg(&flag).is_none());
+    }
+
+    #[test]
+    fn single_value_uses_the_name_or_id() {
+        assert_eq!(shape(Arg::new("out").long("out")), ("<OUT>".into(), None));
+        assert_eq!(
+            shape(Arg::new("out").long("out").value_name("FILE")),
+            ("<FILE>".into(), None)
+        );
+    }
+
+    #[test]
+    fn fixed_count_with_one_name_repeats_it() {
+        let arg = Arg::new("point").long("point").num_args(2).value_name("N");
+        assert_eq!(shape(arg), ("<N> <N>".into(), None));
+    }
+
+    #[test]
+    fn one_name_per_value() {
+        let arg = Arg::new("define")
+            .long("define")
+            .num_args(2)
+            .value_names(["KEY", "VALUE"]);
+        assert_eq!(shape(arg), ("<KEY> <VALUE>".into(), None));
+    }
+
+    #[test]
+    fn optional_value_is_bracketed() {
+        let arg = Arg::new("color")
+            .long("color")
+            .num_args(0..=1)
+            .value_name("WHEN");
+        assert_eq!(shape(arg), ("[<WHEN>]".into(), None));
+    }
+
+    #[test]
+    fn open_ended_and_bounded_counts() {
+        let files = Arg::new("files").num_args(1..).value_name("FILE");
+        assert_eq!(shape(files), ("<FILE>...".into(), None));
+
+        let pairs = Arg::new("pair").long("pair").num_args(2..).value_name("X");
+        assert_eq!(shape(pairs), ("<X>...".into(), Some("2.. values".into())));
+
+        let some = Arg::new("id").long("id").num_args(1..=10).value_name("ID");
+        assert_eq!(
+            shape(some),
+            ("<ID>...".into(), Some("1..=10 values".into()))
+        );
+
+        let none_or_more = Arg::new("extra").num_args(0..).value_name("ARG");
+        assert_eq!(shape(none_or_more), ("[<ARG>...]".into(), None));
+    }
+
+    #[test]
+    fn delimiter_shows_in_the_placeholder() {
+        let arg = Arg::new("tags")
+            .long("tags")
+            .value_delimiter(',')
+            .num_args(1..)
+            .value_name("TAG");
+        assert_eq!(shape(arg), ("<TAG>[,...]".into(), None));
+    }
+
+    #[test]
+    fn hints_from_value_hint_and_parser() {
+        let file = Arg::new("file")
+            .long("file")
+            .value_hint(ValueHint::FilePath);
+        assert_eq!(value(file).hint.as_deref(), Some("file"));
+
+        let jobs = Arg::new("jobs")
+            .long("jobs")
+            .value_parser(clap::value_parser!(u8));
+        assert_eq!(value(jobs).hint.as_deref(), Some("integer"));
+
+        let name = Arg::new("name").long("name");
+        assert_eq!(value(name).hint, None);
+    }
+
+    fn tree() -> Command {
+        Command::new("app")
+            .arg(
+                Arg::new("verbose")
+                    .long("verbose")
+                    .global(true)
+                    .action(ArgAction::SetTrue),
+            )
+            .subcommand(
+                Command::new("commit")
+                    .arg(
+                        Arg::new("amend")
+                            .long("amend")
+                            .action(ArgAction::SetTrue)
+                            .conflicts_with("verbose"),
+                    )
+                    .arg(Arg::new("msg").long("msg").requires("verbose")),
+            )
+    }
+
+    fn option<'a>(page: &'a HelpPage, id: &str) -> &'a HelpOption {
+        page.options.iter().find(|o| o.id == id).expect("option")
+    }
+
+    #[test]
+    fn unbuilt_subcommand_pointing_at_a_parent_global_drops_relations() {
+        let root = tree();
+        let commit = root.find_subcommand("commit").unwrap();
+        let page = HelpPage::from_clap("app", None, "commit", commit);
+
+        assert!(option(&page, "amend").conflicts.is_empty());
+        assert!(option(&page, "msg").requires.is_empty());
+        assert_eq!(page.usage, "Usage: commit [OPTIONS]");
+    }
+
+    #[test]
+    fn argument_page_of_an_unbuilt_subcommand_drops_relations() {
+        let root = tree();
+        let commit = root.find_subcommand("commit").unwrap();
+
+        for id in ["amend", "msg"] {
+            let arg = commit.get_arguments().find(|a| a.get_id() == id).unwrap();
+            let page = HelpArgumentPage::from_clap("app", "commit", commit, arg);
+
+            assert!(page.conflicts.is_empty());
+            assert!(page.requires.is_empty());
+        }
+    }
+
+    #[test]
+    fn built_subcommand_keeps_relations() {
+        let mut root = tree();
+        root.build();
+        let commit = root.find_subcommand("commit").unwrap();
+        let page = HelpPage::from_clap("app", None, "commit", commit);
+
+        assert_eq!(option(&page, "amend").conflicts, vec!["--verbose"]);
+        assert_eq!(option(&page, "msg").requires, vec!["--verbose"]);
+    }
 }
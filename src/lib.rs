@@ -1,28 +1,46 @@
 //! tiny clap help renderer with markdown output and a bit of color.
 //!
 //! the flow is simple:
-//! - parse args with clap and send your match arm into `help_command` (or the `_docs` variant if
-//!   you registered extra notes)
+//! - build a `HelpContext` once (app name, clap tree, docs, theme) and hand it your help args
+//!   (`help_command` and friends still work for one-off calls)
 //! - `HelpPage` turns clap metadata into markdown-friendly structs
 //! - `renderer` prints it with `termimad`, scrolling automatically if it doesn't fit
 //! - `HelpTheme` keeps colors consistent and swappable
 //!
 //! everything is re-exported from here so you rarely need to dig into submodules.
 
+mod context;
 mod doc_registry;
+mod error;
 mod help_command;
 mod help_page;
+mod pager;
+mod relations;
 mod renderer;
+mod search;
+mod styled;
+mod suggest;
 mod theme;
 
+pub use context::{HelpContext, HelpContextBuilder};
 pub use doc_registry::{CommandDoc, DocRegistry};
+pub use error::{HelpError, UnknownTopic};
 pub use help_command::{
-    help_command, help_command_docs, help_command_program, help_command_program_docs, resolve_help, run_help_topic, HelpArgs,
-    HelpTarget,
+    help_command, help_command_docs, help_command_program, help_command_program_docs, resolve_help,
+    run_help_manual, run_help_topic, HelpArgs, HelpTarget,
 };
-pub use help_page::HelpPage;
-pub use renderer::{render_command_help, run_scrollable_help};
-pub use theme::{apply_accent, HelpTheme};
+pub use help_page::{
+    HelpArgumentPage, HelpEnvVar, HelpGuideEntry, HelpPage, HelpPossibleValue, HelpTreeNode,
+    HelpValue, HelpVerbosity,
+};
+pub use pager::PagerPolicy;
+pub use renderer::{
+    command_help_string, guide_string, render_argument_help, render_command_help,
+    render_command_tree, render_guide_index, render_manual, render_search_results,
+    run_scrollable_help, write_command_help, write_guide, OutputPolicy,
+};
+pub use search::{search, SearchHit};
+pub use theme::{apply_accent, ColorPolicy, HelpTheme};
 
 pub use termimad::crossterm::style::Color;
 pub use termimad::MadSkin;
@@ -0,0 +1,64 @@
+//! which pager long help goes through: the built-in scroll view, `$PAGER`, or none at all.
+
+use std::io::{self, Write};
+use std::process::{Command, Stdio};
+
+/// who pages help that doesn't fit on screen.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PagerPolicy {
+    /// the built-in scrollable view.
+    #[default]
+    Builtin,
+
+    /// `$CHKC_HELP_PAGER`, then `$PAGER`, then `less`, fed the rendered (colored) text. falls
+    /// back to the built-in view when the pager can't be started.
+    External,
+
+    /// never page, just print.
+    None,
+}
+
+/// the pager command line, from the environment or `less`.
+fn pager_command() -> Vec<String> {
+    ["CHKC_HELP_PAGER", "PAGER"]
+        .iter()
+        .filter_map(|name| std::env::var(name).ok())
+        .map(|cmd| {
+            cmd.split_whitespace()
+                .map(str::to_string)
+                .collect::<Vec<_>>()
+        })
+        .find(|cmd| !cmd.is_empty())
+        .unwrap_or_else(|| vec!["less".to_string()])
+}
+
+/// pipe `text` through the external pager and wait for it to exit.
+///
+/// fails only when the pager couldn't be started, so the caller can fall back.
+pub(crate) fn run_external(text: &str) -> io::Result<()> {
+    let command = pager_command();
+    let Some((program, args)) = command.split_first() else {
+        return Err(io::Error::other("no pager configured"));
+    };
+
+    let mut pager = Command::new(program);
+    pager.args(args).stdin(Stdio::piped());
+
+    // keep colors, quit if it fits on one screen, don't clear it on exit (same as git).
+    if std::env::var_os("LESS").is_none() {
+        pager.env("LESS", "FRX");
+    }
+    if std::env::var_os("LV").is_none() {
+        pager.env("LV", "-c");
+    }
+
+    let mut child = pager.spawn()?;
+
+    // the pager owns the screen now, and the user may quit before reading everything.
+    if let Some(mut stdin) = child.stdin.take() {
+        let _ = stdin.write_all(text.as_bytes());
+    }
+
+    let _ = child.wait();
+    Ok(())
+}
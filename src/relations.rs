@@ -0,0 +1,375 @@
+//! how arguments relate to each other (conflicts, requirements).
+//!
+//! clap doesn't expose `requires` through a getter, so we ask its parser instead, see
+//! [`RequiresProbe`]. both lookups panic on ids the command doesn't know, which [`Relations`]
+//! guards against.
+
+use clap::error::{ContextKind, ContextValue, ErrorKind};
+use clap::{Arg, ArgAction, Command, ValueHint};
+
+/// how an argument is written on the command line (`--long`, `-s`, or `<NAME>`).
+pub(crate) fn display_name(arg: &Arg) -> String {
+    if let Some(long) = arg.get_long() {
+        format!("--{}", long)
+    } else if let Some(short) = arg.get_short() {
+        format!("-{}", short)
+    } else {
+        let name = arg
+            .get_value_names()
+            .and_then(|v| v.first())
+            .map(|v| v.to_string())
+            .unwrap_or_else(|| arg.get_id().to_string().to_uppercase());
+        format!("<{}>", name)
+    }
+}
+
+/// how an argument is addressed in a topic: `--long`, `-s`, or the positional id.
+pub(crate) fn argument_name(arg: &Arg) -> String {
+    match (arg.get_long(), arg.get_short()) {
+        (Some(long), _) => format!("--{}", long),
+        (None, Some(short)) => format!("-{}", short),
+        (None, None) => arg.get_id().to_string(),
+    }
+}
+
+/// registry key for an argument's extra docs (e.g. `commit.--message`).
+pub(crate) fn argument_key(path: &str, arg: &Arg) -> String {
+    let name = argument_name(arg);
+
+    if path.is_empty() {
+        name
+    } else {
+        format!("{}.{}", path, name)
+    }
+}
+
+/// arguments that can't be used together with `arg`.
+fn conflicts(cmd: &Command, arg: &Arg) -> Vec<String> {
+    cmd.get_arg_conflicts_with(arg)
+        .into_iter()
+        .map(display_name)
+        .collect()
+}
+
+/// conflicts and requirements of one command's arguments.
+///
+/// clap panics when an argument points at an id its command doesn't have. that's the case for a
+/// subcommand taken from a tree that wasn't built (`Command::build` on the root) whose arguments
+/// point at a global of a parent, so unless every id the command refers to is its own, the
+/// relations are left empty.
+pub(crate) struct Relations<'a> {
+    cmd: &'a Command,
+    probe: Option<RequiresProbe>,
+}
+
+impl<'a> Relations<'a> {
+    pub(crate) fn new(cmd: &'a Command) -> Self {
+        Self {
+            cmd,
+            probe: self_contained(cmd).then(|| RequiresProbe::new(cmd)),
+        }
+    }
+
+    /// see [`conflicts`].
+    pub(crate) fn conflicts(&self, arg: &Arg) -> Vec<String> {
+        if self.probe.is_some() {
+            conflicts(self.cmd, arg)
+        } else {
+            Vec::new()
+        }
+    }
+
+    /// see [`RequiresProbe::requires`].
+    pub(crate) fn requires(&self, arg: &Arg) -> Vec<String> {
+        match &self.probe {
+            Some(probe) => probe.requires(arg),
+            None => Vec::new(),
+        }
+    }
+}
+
+/// ids an argument points at, by field of its `Debug` output: a list of ids, or a list of tuples
+/// with the id at the given position.
+const ARG_REFERENCES: &[(&str, Option<usize>)] = &[
+    ("blacklist", None),
+    ("overrides", None),
+    ("requires", Some(1)),
+    ("r_ifs", Some(0)),
+    ("r_unless", None),
+    ("default_vals_ifs", Some(0)),
+];
+
+/// like [`ARG_REFERENCES`], for an `ArgGroup`.
+const GROUP_REFERENCES: &[(&str, Option<usize>)] =
+    &[("args", None), ("requires", None), ("conflicts", None)];
+
+/// whether every id `cmd`'s arguments and groups point at is one of its arguments or groups.
+fn self_contained(cmd: &Command) -> bool {
+    foreign_ids(cmd).is_empty()
+}
+
+/// a copy of `cmd` clap can build on its own (e.g. to render its usage): ids pointing outside of
+/// it get hidden stand-in flags.
+pub(crate) fn standalone(cmd: &Command) -> Command {
+    foreign_ids(cmd).into_iter().fold(cmd.clone(), |cmd, id| {
+        let long = format!("chkc-help-stand-in-{}", id);
+        cmd.arg(
+            Arg::new(id)
+                .long(long)
+                .action(ArgAction::SetTrue)
+                .hide(true),
+        )
+    })
+}
+
+/// ids `cmd`'s arguments and groups point at that aren't among its arguments or groups.
+///
+/// clap has no getters for `conflicts_with`, `requires` and friends, so the ids are read from the
+/// `Debug` output. `required_if_eq_all` and `required_unless_present_all` don't show up there.
+fn foreign_ids(cmd: &Command) -> Vec<String> {
+    let mut known: Vec<String> = cmd
+        .get_arguments()
+        .map(|a| a.get_id().to_string())
+        .chain(cmd.get_groups().map(|g| g.get_id().to_string()))
+        .collect();
+    // `Arg::group` only creates the group once the command is built.
+    known.extend(
+        cmd.get_arguments()
+            .flat_map(|a| debug_ids(&format!("{:?}", a), &[("groups", None)])),
+    );
+
+    let args = cmd
+        .get_arguments()
+        .flat_map(|a| debug_ids(&format!("{:?}", a), ARG_REFERENCES));
+    let groups = cmd
+        .get_groups()
+        .flat_map(|g| debug_ids(&format!("{:?}", g), GROUP_REFERENCES));
+
+    let mut foreign: Vec<String> = args
+        .chain(groups)
+        .filter(|id| !known.contains(id))
+        .collect();
+    foreign.sort();
+    foreign.dedup();
+    foreign
+}
+
+/// the ids in `fields` of a derived-style `Debug` output (`Name { field: [...], .. }`).
+fn debug_ids(debug: &str, fields: &[(&str, Option<usize>)]) -> Vec<String> {
+    let mut ids = Vec::new();
+    let mut depth = 0;
+    let mut word = String::new();
+    let mut field = None;
+    let mut element = 0;
+    let mut chars = debug.chars();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '"' => {
+                let mut string = String::new();
+                while let Some(c) = chars.next() {
+                    match c {
+                        '\\' => string.extend(chars.next()),
+                        '"' => break,
+                        c => string.push(c),
+                    }
+                }
+
+                let wanted = match field {
+                    Some(None) => depth == 2,
+                    Some(Some(position)) => depth == 3 && element == position,
+                    None => false,
+                };
+                if wanted {
+                    ids.push(string);
+                }
+            }
+            '{' | '[' | '(' => {
+                depth += 1;
+                if depth == 3 {
+                    element = 0;
+                }
+            }
+            '}' | ']' | ')' => depth -= 1,
+            ',' if depth == 3 => element += 1,
+            ':' if depth == 1 => {
+                field = fields
+                    .iter()
+                    .find(|(name, _)| *name == word)
+                    .map(|(_, position)| *position);
+            }
+            c if depth == 1 && (c.is_alphanumeric() || c == '_') => {
+                word.push(c);
+                continue;
+            }
+            _ => {}
+        }
+        word.clear();
+    }
+
+    ids
+}
+
+/// finds what each argument of one command requires, by asking clap's parser.
+///
+/// the probe copies the command's arguments into a fresh command, turns the argument under test
+/// into a plain flag (so no value has to be made up for it) and diffs the "missing required
+/// argument" report against the one for an empty command line, computed once.
+///
+/// `cmd` has to be self-contained, see [`Relations`].
+struct RequiresProbe {
+    cmd: Command,
+    baseline: Vec<String>,
+}
+
+impl RequiresProbe {
+    fn new(cmd: &Command) -> Self {
+        // other arguments' env vars are read while parsing and may not be valid values.
+        let cmd = Command::new("probe")
+            .no_binary_name(true)
+            .disable_help_flag(true)
+            .disable_version_flag(true)
+            .args(cmd.get_arguments().map(|a| a.clone().env(None::<&str>)))
+            .groups(cmd.get_groups().cloned());
+        let baseline = missing_args(cmd.clone(), &[]);
+
+        Self { cmd, baseline }
+    }
+
+    /// arguments that become mandatory once `arg` is passed, written like [`display_name`] (a
+    /// group reads "--a or --b"). positionals are skipped, they can't be passed without a value.
+    fn requires(&self, arg: &Arg) -> Vec<String> {
+        let Some(flag) = arg
+            .get_long()
+            .map(|l| format!("--{}", l))
+            .or_else(|| arg.get_short().map(|s| format!("-{}", s)))
+            .filter(|_| !arg.is_positional())
+        else {
+            return Vec::new();
+        };
+        if !self.cmd.get_arguments().any(|a| a.get_id() == arg.get_id()) {
+            return Vec::new();
+        }
+
+        let cmd = self.cmd.clone().mut_arg(arg.get_id(), |a| {
+            a.action(ArgAction::SetTrue)
+                .num_args(0)
+                .value_parser(clap::value_parser!(bool))
+                .value_names(Vec::<&str>::new())
+                .value_delimiter(None::<char>)
+                .value_hint(ValueHint::Unknown)
+                // value-only settings clap refuses on a flag.
+                .require_equals(false)
+                .allow_hyphen_values(false)
+                .allow_negative_numbers(false)
+                .hide_possible_values(false)
+                .hide_default_value(false)
+                .ignore_case(false)
+                .last(false)
+                .default_value(None::<&str>)
+                .default_missing_value(None::<&str>)
+        });
+
+        missing_args(cmd, &[flag])
+            .into_iter()
+            .filter(|missing| !self.baseline.contains(missing))
+            .filter_map(|missing| self.display(&missing))
+            .collect()
+    }
+
+    /// map one of clap's usage strings (`--author <NAME>`, `<FILE>...`, or a group like
+    /// `<--stdin|--file <FILE>>`) back to its arguments. `None` if any part isn't recognised.
+    fn display(&self, missing: &str) -> Option<String> {
+        let group = missing
+            .strip_prefix('<')
+            .and_then(|m| m.strip_suffix('>'))
+            .filter(|inner| inner.contains('|') || inner.starts_with('-'));
+
+        match group {
+            Some(inner) => {
+                let members = inner
+                    .split('|')
+                    .map(|member| self.find(member).map(display_name))
+                    .collect::<Option<Vec<_>>>()?;
+                Some(members.join(" or "))
+            }
+            None => self.find(missing).map(display_name),
+        }
+    }
+
+    /// the argument a usage string starts with: `--long`, `-s`, or a positional's value name.
+    fn find(&self, usage: &str) -> Option<&Arg> {
+        let mut args = self.cmd.get_arguments();
+
+        if let Some(rest) = usage.strip_prefix("--") {
+            let long = rest.split([' ', '=', '[', '.']).next()?;
+            return args.find(|a| a.get_long() == Some(long));
+        }
+
+        if let Some(rest) = usage.strip_prefix('-') {
+            let short = rest.chars().next()?;
+            return args.find(|a| a.get_short() == Some(short));
+        }
+
+        let name = usage
+            .split(' ')
+            .next()?
+            .trim_matches(|c| matches!(c, '<' | '>' | '[' | ']' | '.'));
+        args.filter(|a| a.is_positional()).find(|a| {
+            a.get_id().as_str().eq_ignore_ascii_case(name)
+                || a.get_value_names()
+                    .is_some_and(|names| names.iter().any(|n| n.eq_ignore_ascii_case(name)))
+        })
+    }
+}
+
+fn missing_args(cmd: Command, args: &[String]) -> Vec<String> {
+    match cmd.try_get_matches_from(args) {
+        Err(err) if err.kind() == ErrorKind::MissingRequiredArgument => {
+            match err.get(ContextKind::InvalidArg) {
+                Some(ContextValue::Strings(missing)) => missing.clone(),
+                _ => Vec::new(),
+            }
+        }
+        _ => Vec::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::ArgGroup;
+
+    #[test]
+    fn debug_ids_reads_lists_and_tuples() {
+        let arg = Arg::new("a")
+            .help("conflicts_with: [\"nope\"]")
+            .conflicts_with("b")
+            .requires("c")
+            .requires_if("x", "d")
+            .required_if_eq("e", "y")
+            .default_value_if("f", "z", Some("w"));
+
+        let mut ids = debug_ids(&format!("{:?}", arg), ARG_REFERENCES);
+        ids.sort();
+        assert_eq!(ids, vec!["b", "c", "d", "e", "f"]);
+
+        let group = ArgGroup::new("g").args(["a", "b"]).conflicts_with("c");
+        assert_eq!(
+            debug_ids(&format!("{:?}", group), GROUP_REFERENCES),
+            vec!["a", "b", "c"]
+        );
+    }
+
+    #[test]
+    fn ids_from_elsewhere_are_not_self_contained() {
+        let cmd = Command::new("app")
+            .arg(Arg::new("a").long("a").conflicts_with("b"))
+            .arg(Arg::new("b").long("b").group("inputs"))
+            .arg(Arg::new("c").long("c").requires("inputs"));
+        assert!(self_contained(&cmd));
+
+        let cmd = cmd.arg(Arg::new("d").long("d").requires("verbose"));
+        assert!(!self_contained(&cmd));
+    }
+}
@@ -1,80 +1,853 @@
 //! takes a [`HelpPage`] and paints it with termimad, scrolling when the text is too long.
 
-use std::io::{stdout, Write};
+use std::io::{stdout, IsTerminal, Write};
 
 use termimad::crossterm::event::KeyCode::*;
 use termimad::crossterm::event::{self, Event};
 use termimad::crossterm::style::{Color, Stylize};
 use termimad::crossterm::{queue, QueueableCommand};
-use termimad::MadView;
-
-use crate::help_page::HelpPage;
+use termimad::{MadSkin, MadView};
+
+use crate::error::{HelpError, UnknownTopic};
+use crate::help_page::{
+    HelpArgumentPage, HelpEnvVar, HelpGuideEntry, HelpOption, HelpPage, HelpPossibleValue,
+    HelpTreeNode, HelpValue, HelpVerbosity,
+};
+use crate::pager::{self, PagerPolicy};
+use crate::search::SearchHit;
 use crate::theme::HelpTheme;
 
 /// render a help page, falling back to a scrollable view if it doesn't fit on screen.
-pub fn render_command_help(theme: &HelpTheme, page: &HelpPage) {
+pub fn render_command_help(theme: &HelpTheme, page: &HelpPage) -> Result<(), HelpError> {
+    let md = page_markdown(page, 1);
+    show(
+        theme,
+        &page.app_name,
+        md,
+        OutputPolicy::Auto,
+        None,
+        PagerPolicy::Builtin,
+    )
+}
+
+/// write a command page into any sink (stderr, a log file, a buffer), laid out at `width` columns.
+pub fn write_command_help<W: Write>(
+    out: &mut W,
+    theme: &HelpTheme,
+    page: &HelpPage,
+    width: usize,
+) -> Result<(), HelpError> {
+    write_markdown(
+        out,
+        theme,
+        &page_skin(theme),
+        &page_markdown(page, 1),
+        width,
+    )
+}
+
+/// a command page as a string, laid out at `width` columns.
+pub fn command_help_string(theme: &HelpTheme, page: &HelpPage, width: usize) -> String {
+    detached_text(theme, &page_skin(theme), &page_markdown(page, 1), width)
+}
+
+/// write a markdown guide into any sink, laid out at `width` columns.
+pub fn write_guide<W: Write>(
+    out: &mut W,
+    theme: &HelpTheme,
+    guide: &str,
+    width: usize,
+) -> Result<(), HelpError> {
+    write_markdown(out, theme, &theme.skin, guide, width)
+}
+
+/// a markdown guide as a string, laid out at `width` columns.
+pub fn guide_string(theme: &HelpTheme, guide: &str, width: usize) -> String {
+    detached_text(theme, &theme.skin, guide, width)
+}
+
+/// render several pages as one document with a table of contents (`help --all`).
+///
+/// the first page is expected to be the program page; it names the manual.
+pub fn render_manual(theme: &HelpTheme, pages: &[HelpPage]) -> Result<(), HelpError> {
+    let Some(program) = pages.first() else {
+        return Ok(());
+    };
+
+    show(
+        theme,
+        &program.app_name,
+        manual_markdown(pages),
+        OutputPolicy::Auto,
+        None,
+        PagerPolicy::Builtin,
+    )
+}
+
+pub(crate) fn manual_markdown(pages: &[HelpPage]) -> String {
+    let Some(program) = pages.first() else {
+        return String::new();
+    };
+
     let mut md = String::new();
 
-    render_header(&mut md, page);
+    md.push_str(&format!("# {} manual\n", program.app_name));
+    md.push_str("**Contents:**\n");
+    for page in pages {
+        md.push_str(&format!("- `{}`", page_title(page)));
+        if let Some(summary) = page.summary.as_deref().filter(|s| !s.is_empty()) {
+            md.push_str(&format!(": {}", summary));
+        }
+        md.push('\n');
+    }
+
+    for page in pages {
+        md.truncate(md.trim_end().len());
+        md.push_str("\n\n---\n");
+        md.push_str(&page_markdown(page, 2));
+    }
+
+    md
+}
+
+pub(crate) fn page_markdown(page: &HelpPage, level: usize) -> String {
+    let mut md = String::new();
+
+    render_header(&mut md, page, level);
     render_usage(&mut md, page);
     render_subcommands(&mut md, page);
     render_positionals(&mut md, page);
     render_options(&mut md, page);
-    render_examples(&mut md, page);
-    render_notes(&mut md, page);
+    render_hidden(&mut md, page);
+    render_examples(&mut md, &page.examples);
+    if page.verbosity == HelpVerbosity::Long {
+        render_notes(&mut md, &page.notes);
+    }
+    render_globals(&mut md, page);
+    render_epilogue(&mut md, page);
+    render_guide_hint(&mut md, page);
+
+    md
+}
+
+/// list every registered guide along with the topic that opens it.
+pub fn render_guide_index(
+    theme: &HelpTheme,
+    app_name: &str,
+    guides: &[HelpGuideEntry],
+) -> Result<(), HelpError> {
+    let md = guide_index_markdown(app_name, guides);
+    show(
+        theme,
+        app_name,
+        md,
+        OutputPolicy::Auto,
+        None,
+        PagerPolicy::Builtin,
+    )
+}
+
+pub(crate) fn guide_index_markdown(app_name: &str, guides: &[HelpGuideEntry]) -> String {
+    let mut md = String::new();
+
+    md.push_str(&format!("# {} guides\n", app_name));
+
+    if guides.is_empty() {
+        md.push_str("No guides are registered.\n");
+        return md;
+    }
+
+    md.push_str("|:-|:-\n");
+    md.push_str("| topic | guide |\n");
+    md.push_str("|:-|:-\n");
+
+    let mut guides = guides.to_vec();
+    guides.sort_by(|a, b| a.path.cmp(&b.path).then_with(|| a.name.cmp(&b.name)));
 
-    let (_, rows) = termimad::crossterm::terminal::size().unwrap();
-    if md.lines().count() > rows.into() {
-        let _ = run_scrollable_help(theme, &page.app_name, md);
+    for guide in &guides {
+        md.push_str(&format!(
+            "| `{}` | {} |\n",
+            guide.topic(),
+            guide.title.as_deref().unwrap_or("")
+        ));
+    }
+
+    md.push_str("|-\n");
+
+    md
+}
+
+/// draw the subcommand hierarchy with box-drawing connectors and each command's summary.
+pub fn render_command_tree(
+    theme: &HelpTheme,
+    app_name: &str,
+    tree: &HelpTreeNode,
+) -> Result<(), HelpError> {
+    let md = tree_markdown(app_name, tree);
+    show(
+        theme,
+        app_name,
+        md,
+        OutputPolicy::Auto,
+        None,
+        PagerPolicy::Builtin,
+    )
+}
+
+pub(crate) fn tree_markdown(app_name: &str, tree: &HelpTreeNode) -> String {
+    let mut rows = Vec::new();
+    tree_rows(&mut rows, tree, String::new(), String::new());
+
+    let width = rows
+        .iter()
+        .map(|(prefix, _)| unicode_width::UnicodeWidthStr::width(prefix.as_str()))
+        .max()
+        .unwrap_or_default();
+
+    let mut md = String::new();
+    md.push_str(&format!("# {} command tree\n", app_name));
+
+    for (prefix, node) in rows {
+        let pad = width - unicode_width::UnicodeWidthStr::width(prefix.as_str());
+        md.push_str(&prefix);
+
+        let mut tail = Vec::new();
+        if let Some(summary) = node.summary.as_deref().filter(|s| !s.is_empty()) {
+            tail.push(summary.to_string());
+        }
+        if node.has_docs {
+            tail.push("~~docs~~".to_string());
+        }
+        if node.has_guide {
+            tail.push("~~guide~~".to_string());
+        }
+        if node.hidden {
+            tail.push("*(hidden)*".to_string());
+        }
+        if node.truncated > 0 {
+            tail.push(format!("*(+{} more)*", node.truncated));
+        }
+
+        if !tail.is_empty() {
+            md.push_str(&" ".repeat(pad + 2));
+            md.push_str(&tail.join(" "));
+        }
+        md.push('\n');
+    }
+
+    md.push_str("\n*~~docs~~ has extra docs, ~~guide~~ has a guide (`help <command> guide`)*\n");
+
+    md
+}
+
+fn tree_rows<'a>(
+    rows: &mut Vec<(String, &'a HelpTreeNode)>,
+    node: &'a HelpTreeNode,
+    prefix: String,
+    indent: String,
+) {
+    rows.push((format!("{}{}", prefix, node.name), node));
+
+    for (i, child) in node.children.iter().enumerate() {
+        let last = i + 1 == node.children.len();
+        // non-breaking spaces: a line starting with four plain ones would be a code block.
+        let (branch, next) = if last {
+            ("└── ", "\u{a0}\u{a0}\u{a0}\u{a0}")
+        } else {
+            ("├── ", "│   ")
+        };
+
+        tree_rows(
+            rows,
+            child,
+            format!("{}{}", indent, branch),
+            format!("{}{}", indent, next),
+        );
+    }
+}
+
+/// list search hits with their snippet and the topic that opens them.
+pub fn render_search_results(
+    theme: &HelpTheme,
+    app_name: &str,
+    terms: &[String],
+    hits: &[SearchHit],
+) -> Result<(), HelpError> {
+    let md = search_markdown(app_name, terms, hits);
+    show(
+        theme,
+        app_name,
+        md,
+        OutputPolicy::Auto,
+        None,
+        PagerPolicy::Builtin,
+    )
+}
+
+pub(crate) fn search_markdown(app_name: &str, terms: &[String], hits: &[SearchHit]) -> String {
+    let mut md = String::new();
+
+    md.push_str(&format!("# {} search: {}\n", app_name, terms.join(" ")));
+
+    if terms.is_empty() {
+        md.push_str("Give some terms to search for, e.g. `help search commit message`.\n");
+    } else if hits.is_empty() {
+        md.push_str("Nothing matched.\n");
     } else {
-        let mut doc_skin = theme.skin.clone();
-        doc_skin.headers[0].align = termimad::Alignment::Left;
-        print!("{}", doc_skin.term_text(&md));
+        md.push_str(&format!(
+            "*{} result{}*\n\n",
+            hits.len(),
+            if hits.len() == 1 { "" } else { "s" }
+        ));
+
+        for hit in hits {
+            md.push_str(&format!("- `{}` *({})*\n", hit.topic, hit.kind));
+            if !hit.snippet.is_empty() {
+                md.push_str(&format!("  {}\n", hit.snippet));
+            }
+        }
     }
+
+    md
+}
+
+/// a markdown guide, listing `others` (e.g. the named guides next to a default one) below it.
+pub(crate) fn guide_markdown(guide: &str, others: &[HelpGuideEntry]) -> String {
+    let mut md = guide.to_string();
+
+    if !others.is_empty() {
+        md.truncate(md.trim_end().len());
+        md.push_str("\n\n**More guides:**\n");
+        for other in others {
+            md.push_str(&format!("- `{}`", other.topic()));
+            if let Some(title) = &other.title {
+                md.push_str(&format!(" {}", title));
+            }
+            md.push('\n');
+        }
+    }
+
+    md
+}
+
+/// render the detail page of a single argument, scrolling the same way command pages do.
+pub fn render_argument_help(theme: &HelpTheme, page: &HelpArgumentPage) -> Result<(), HelpError> {
+    let md = argument_markdown(page);
+    show(
+        theme,
+        &page.app_name,
+        md,
+        OutputPolicy::Auto,
+        None,
+        PagerPolicy::Builtin,
+    )
 }
 
-fn render_header(md: &mut String, page: &HelpPage) {
+pub(crate) fn argument_markdown(page: &HelpArgumentPage) -> String {
+    let mut md = String::new();
+
+    md.push_str(&format!("# {}", page.app_name));
     if !page.path.is_empty() {
-        md.push_str(&format!("# {} {}\n", page.app_name, page.path.replace(".", " ")))
+        md.push_str(&format!(" {}", page.path.replace('.', " ")));
+    }
+    md.push_str(&format!(" {}\n", page.name));
+
+    let mut kind = vec![if page.positional {
+        "~~argument~~"
+    } else {
+        "~~option~~"
+    }];
+    if page.required {
+        kind.push("~~required~~");
+    }
+    md.push_str(&format!("*({})*\n", kind.join(", ")));
+
+    let desc = page.description.as_deref().filter(|s| !s.is_empty());
+
+    // clap's long help usually repeats the short help as its first paragraph.
+    if let Some(summary) = page.summary.as_deref().filter(|s| !s.is_empty()) {
+        if !desc.is_some_and(|d| d.starts_with(summary)) {
+            md.push_str(&format!("{}\n\n", summary));
+        }
+    }
+
+    if let Some(desc) = desc {
+        md.push_str(&format!("{}\n\n", desc));
+    }
+
+    if !page.positional && (page.short.is_some() || page.long.is_some()) {
+        let mut names = Vec::new();
+        if let Some(short) = page.short {
+            names.push(format!("`-{}`", short));
+        }
+        if let Some(long) = &page.long {
+            names.push(format!("`--{}`", long));
+        }
+        names.extend(page.short_aliases.iter().map(|c| format!("`-{}`", c)));
+        names.extend(page.aliases.iter().map(|l| format!("`--{}`", l)));
+        md.push_str(&format!("**Flags:** {}\n", names.join(", ")));
+    }
+
+    if let Some(value) = &page.value {
+        md.push_str(&format!("**Value:** `{}`", value.placeholder()));
+        if let Some(badge) = value_badge(value) {
+            md.push_str(&format!(" {}", badge));
+        }
+        md.push('\n');
+    }
+
+    let values: Vec<&HelpPossibleValue> =
+        page.possible_values.iter().filter(|v| !v.hidden).collect();
+    let described = values.iter().any(|v| v.help.is_some());
+
+    if !values.is_empty() && !described {
+        let values = values
+            .iter()
+            .map(|v| format!("~~{}~~", v.name))
+            .collect::<Vec<_>>()
+            .join(", ");
+        md.push_str(&format!("**Possible values:** {}\n", values));
+    }
+
+    if !page.default.is_empty() {
+        md.push_str(&format!("**Default:** `{}`\n", page.default));
+    }
+
+    if let Some(env) = &page.env {
+        md.push_str(&format!("**Environment:** `{}`", env.name));
+        match (env.value.as_ref().filter(|_| page.show_env_values), env.set) {
+            (Some(value), _) => md.push_str(&format!(" *(currently `{}`)*", value)),
+            (None, true) => md.push_str(" *(currently set)*"),
+            (None, false) => md.push_str(" *(not set)*"),
+        }
+        md.push('\n');
+    }
+
+    if !page.conflicts.is_empty() {
+        md.push_str(&format!(
+            "**Conflicts with:** {}\n",
+            code_list(&page.conflicts)
+        ));
+    }
+
+    if !page.requires.is_empty() {
+        md.push_str(&format!("**Requires:** {}\n", code_list(&page.requires)));
+    }
+
+    md.push('\n');
+
+    if described {
+        md.push_str("**Possible values:**\n");
+        md.push_str("|:-|:-\n");
+        md.push_str("| value | description |\n");
+        md.push_str("|:-|:-\n");
+
+        for value in values {
+            md.push_str(&format!(
+                "| ~~{}~~ | {} |\n",
+                value.name,
+                value.help.as_deref().unwrap_or("")
+            ));
+        }
+
+        md.push_str("|-\n\n");
+    }
+    render_examples(&mut md, &page.examples);
+    render_notes(&mut md, &page.notes);
+
+    md
+}
+
+/// how rendered help reaches the terminal.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum OutputPolicy {
+    /// print straight away, or open the scrollable view if it's taller than the terminal.
+    #[default]
+    Auto,
+
+    /// always open the scrollable view.
+    Scroll,
+
+    /// always print straight away.
+    Print,
+}
+
+/// wrap width for plain output when nobody picked one.
+pub(crate) const DEFAULT_WIDTH: usize = 80;
+
+/// show a help page with a left-aligned title.
+pub(crate) fn show(
+    theme: &HelpTheme,
+    app_name: &str,
+    md: String,
+    output: OutputPolicy,
+    width: Option<usize>,
+    pager: PagerPolicy,
+) -> Result<(), HelpError> {
+    let theme = theme.for_terminal();
+    present(
+        &theme,
+        &page_skin(&theme),
+        app_name,
+        md,
+        output,
+        width,
+        pager,
+    )
+}
+
+/// show a guide as its author laid it out.
+pub(crate) fn show_guide(
+    theme: &HelpTheme,
+    app_name: &str,
+    md: String,
+    output: OutputPolicy,
+    width: Option<usize>,
+    pager: PagerPolicy,
+) -> Result<(), HelpError> {
+    let theme = theme.for_terminal();
+    present(&theme, &theme.skin, app_name, md, output, width, pager)
+}
+
+/// the theme skin with a left-aligned title, for generated pages.
+pub(crate) fn page_skin(theme: &HelpTheme) -> MadSkin {
+    let mut skin = theme.skin.clone();
+    skin.headers[0].align = termimad::Alignment::Left;
+    skin
+}
+
+/// lay markdown out at `width` columns into any sink.
+pub(crate) fn write_markdown<W: Write>(
+    out: &mut W,
+    theme: &HelpTheme,
+    skin: &MadSkin,
+    md: &str,
+    width: usize,
+) -> Result<(), HelpError> {
+    write!(out, "{}", detached_text(theme, skin, md, width))?;
+    out.flush()?;
+    Ok(())
+}
+
+/// pick between paging and printing, or plain text when stdout isn't a terminal (pipes, CI),
+/// where we never touch raw mode, the terminal size or a pager.
+fn present(
+    theme: &HelpTheme,
+    skin: &MadSkin,
+    app_name: &str,
+    md: String,
+    output: OutputPolicy,
+    width: Option<usize>,
+    pager: PagerPolicy,
+) -> Result<(), HelpError> {
+    let mut out = stdout().lock();
+
+    if !out.is_terminal() {
+        let text = detached_text(theme, skin, &md, width.unwrap_or(DEFAULT_WIDTH));
+        out.write_all(text.as_bytes())?;
+        out.flush()?;
+        return Ok(());
+    }
+
+    let scroll = match output {
+        _ if pager == PagerPolicy::None => false,
+        OutputPolicy::Scroll => true,
+        OutputPolicy::Print => false,
+        OutputPolicy::Auto => termimad::crossterm::terminal::size()
+            .is_ok_and(|(_, rows)| md.lines().count() > rows.into()),
+    };
+
+    if scroll {
+        drop(out);
+
+        if pager == PagerPolicy::External
+            && pager::run_external(&skin.term_text(&md).to_string()).is_ok()
+        {
+            return Ok(());
+        }
+
+        run_scrollable_help(theme, app_name, md)
+    } else {
+        write!(out, "{}", skin.term_text(&md))?;
+        out.flush()?;
+        Ok(())
+    }
+}
+
+/// markdown laid out for something that isn't a terminal: styled only when colors are forced.
+fn detached_text(theme: &HelpTheme, skin: &MadSkin, md: &str, width: usize) -> String {
+    if theme.use_color(false) {
+        skin.text(md, Some(width)).to_string()
+    } else {
+        plain_text(skin, md, width)
+    }
+}
+
+/// markdown laid out at `width` columns with no styling and no escape codes.
+fn plain_text(skin: &MadSkin, md: &str, width: usize) -> String {
+    let mut plain = MadSkin::no_style();
+    for (header, styled) in plain.headers.iter_mut().zip(&skin.headers) {
+        header.align = styled.align;
+    }
+
+    strip_ansi(&plain.text(md, Some(width)).to_string())
+}
+
+fn code_list(items: &[String]) -> String {
+    items
+        .iter()
+        .map(|i| format!("`{}`", i))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// explain why a topic didn't resolve, with close matches and the valid topics at that level.
+pub(crate) fn render_unknown_topic(theme: &HelpTheme, err: &UnknownTopic) {
+    let mut md = String::new();
+
+    md.push_str(&format!("**Unknown help topic:** ~~{}~~\n", err.segment));
+
+    match err.suggestions.as_slice() {
+        [] => {}
+        [only] => md.push_str(&format!("Did you mean ~~{}~~?\n", only)),
+        many => md.push_str(&format!(
+            "Did you mean one of {}?\n",
+            many.iter()
+                .map(|s| format!("~~{}~~", s))
+                .collect::<Vec<_>>()
+                .join(", ")
+        )),
+    }
+
+    if !err.children.is_empty() {
+        md.push('\n');
+        if err.path.is_empty() {
+            md.push_str("**Available topics:**\n");
+        } else {
+            md.push_str(&format!(
+                "**Available topics under `{}`:**\n",
+                err.path.join(" ")
+            ));
+        }
+
+        for child in &err.children {
+            md.push_str(&format!("- {}\n", child));
+        }
+    }
+
+    if std::io::stderr().is_terminal() {
+        eprint!("{}", theme.for_terminal().skin.term_text(&md));
+    } else {
+        eprint!("{}", detached_text(theme, &theme.skin, &md, DEFAULT_WIDTH));
+    }
+}
+
+fn page_title(page: &HelpPage) -> String {
+    if !page.path.is_empty() {
+        format!("{} {}", page.app_name, page.path.replace(".", " "))
     } else if let Some(version) = &page.version {
-        md.push_str(&format!("# {} v{}\n", page.app_name, version));
+        format!("{} v{}", page.app_name, version)
     } else {
-        md.push_str(&format!("# {}\n", page.app_name));
+        page.app_name.clone()
+    }
+}
+
+fn render_header(md: &mut String, page: &HelpPage, level: usize) {
+    md.push_str(&format!("{} {}\n", "#".repeat(level), page_title(page)));
+
+    if let Some(invoked_as) = &page.invoked_as {
+        md.push_str(&format!("*(invoked as* ~~{}~~*)*\n", invoked_as));
+    }
+
+    if let Some(prologue) = extra_help(page, &page.before_help, &page.before_long_help) {
+        md.push_str(&format!("{}\n\n", prologue));
+    }
+
+    if page.verbosity == HelpVerbosity::Short {
+        // like clap's `-h`, fall back to the long text when there's no short one.
+        let summary = page
+            .summary
+            .as_deref()
+            .or(page.description.as_deref())
+            .map(first_line)
+            .unwrap_or_default();
+        if !summary.is_empty() {
+            md.push_str(&format!("{}\n\n", summary));
+        }
+        return;
     }
 
     if let Some(summary) = &page.summary {
         if !summary.is_empty() {
             md.push_str(&format!("{}\n", summary));
-            md.push_str("\n");
+            md.push('\n');
         }
     }
 
     if let Some(desc) = &page.description {
         if !desc.is_empty() {
             md.push_str(&format!("{}\n", desc));
-            md.push_str("\n");
+            md.push('\n');
         }
     }
 }
 
+/// clap's before/after help for the page's verbosity: long help falls back to the short text,
+/// short help doesn't borrow the long one.
+fn extra_help<'a>(
+    page: &HelpPage,
+    short: &'a Option<String>,
+    long: &'a Option<String>,
+) -> Option<&'a str> {
+    let text = match page.verbosity {
+        HelpVerbosity::Short => short.as_deref(),
+        HelpVerbosity::Long => long.as_deref().or(short.as_deref()),
+    };
+
+    text.map(str::trim).filter(|t| !t.is_empty())
+}
+
+fn render_epilogue(md: &mut String, page: &HelpPage) {
+    if let Some(epilogue) = extra_help(page, &page.after_help, &page.after_long_help) {
+        md.truncate(md.trim_end().len());
+        md.push_str(&format!("\n\n{}\n", epilogue));
+    }
+}
+
+/// first non-empty line of a help text.
+fn first_line(text: &str) -> &str {
+    text.lines()
+        .map(str::trim)
+        .find(|l| !l.is_empty())
+        .unwrap_or_default()
+}
+
+/// argument help for the page's verbosity, squashed onto one line so it fits a table row.
+fn arg_help(page: &HelpPage, help: Option<&str>, long_help: Option<&str>) -> String {
+    let help = help.filter(|h| !h.is_empty());
+
+    match page.verbosity {
+        HelpVerbosity::Short => help
+            .or(long_help)
+            .map(first_line)
+            .unwrap_or_default()
+            .to_string(),
+        HelpVerbosity::Long => long_help
+            .or(help)
+            .map(|h| h.split_whitespace().collect::<Vec<_>>().join(" "))
+            .unwrap_or_default(),
+    }
+}
+
 fn render_usage(md: &mut String, page: &HelpPage) {
     let mut usage = page.usage.replace("Usage:", "").trim().to_owned();
-    let binding = std::env::current_exe().expect("Failed to get executable path");
-    let exec_name = binding
-        .file_name()
-        .expect("Failed to get executable name")
-        .to_str()
-        .unwrap();
 
-    if !usage.starts_with(exec_name) {
-        usage.insert_str(0, &format!("{} ", exec_name));
+    if !usage.starts_with(&page.bin_name) {
+        usage.insert_str(0, &format!("{} ", page.bin_name));
     }
 
     md.push_str(&format!("**Usage:** `{}`\n", usage));
+
+    if !page.groups.is_empty() {
+        md.push_str(&format!("**Synopsis:** `{}`\n", synopsis(page)));
+    }
+}
+
+/// usage line that spells out argument groups (`[--json | --yaml]`), which clap folds away.
+fn synopsis(page: &HelpPage) -> String {
+    let mut parts = vec![page.bin_name.clone()];
+    parts.extend(
+        page.path
+            .split('.')
+            .filter(|s| !s.is_empty())
+            .map(str::to_string),
+    );
+    parts.extend(page.groups.iter().map(|g| g.synopsis()));
+
+    let grouped = |groups: &[String]| {
+        groups
+            .iter()
+            .any(|id| page.groups.iter().any(|g| &g.id == id))
+    };
+    let options: Vec<&HelpOption> = page
+        .options
+        .iter()
+        .filter(|opt| !opt.hidden && !grouped(&opt.groups))
+        .collect();
+
+    for opt in options.iter().filter(|opt| opt.required) {
+        let mut name = match (&opt.long, opt.short) {
+            (Some(long), _) => format!("--{}", long),
+            (None, Some(short)) => format!("-{}", short),
+            (None, None) => continue,
+        };
+        if let Some(value) = &opt.value {
+            name.push_str(&format!(" {}", value.placeholder()));
+        }
+        parts.push(name);
+    }
+
+    if options.iter().any(|opt| !opt.required) {
+        parts.push("[OPTIONS]".to_string());
+    }
+
+    for arg in page
+        .positionals
+        .iter()
+        .filter(|arg| !arg.hidden && !grouped(&arg.groups))
+    {
+        let name = arg.name.to_uppercase();
+        let dots = if arg.multiple { "..." } else { "" };
+        if arg.required {
+            parts.push(format!("<{}>{}", name, dots));
+        } else {
+            parts.push(format!("[{}]{}", name, dots));
+        }
+    }
+
+    if page.subcommands.iter().any(|sc| !sc.hidden) {
+        parts.push("<COMMAND>".to_string());
+    }
+
+    parts.join(" ")
+}
+
+/// `(required, conflicts with --amend, one of: --json, --yaml)` for an argument, if any apply.
+fn relation_badges(
+    page: &HelpPage,
+    required: bool,
+    conflicts: &[String],
+    requires: &[String],
+    groups: &[String],
+) -> Option<String> {
+    let mut badges = Vec::new();
+
+    if required {
+        badges.push("~~required~~".to_string());
+    }
+
+    for group in page.groups.iter().filter(|g| groups.contains(&g.id)) {
+        let label = match (group.multiple, group.required) {
+            (false, true) => "exactly one of",
+            (false, false) => "one of",
+            (true, _) => "at least one of",
+        };
+        badges.push(format!("{}: {}", label, group.args.join(", ")));
+    }
+
+    if !conflicts.is_empty() {
+        badges.push(format!("conflicts with {}", conflicts.join(", ")));
+    }
+
+    if !requires.is_empty() {
+        badges.push(format!("requires {}", requires.join(", ")));
+    }
+
+    (!badges.is_empty()).then(|| format!("*({})*", badges.join(", ")))
 }
 
 fn render_subcommands(md: &mut String, page: &HelpPage) {
-    if page.subcommands.is_empty() {
+    if page.subcommands.iter().all(|sc| sc.hidden) {
         return;
     }
 
@@ -84,30 +857,37 @@ fn render_subcommands(md: &mut String, page: &HelpPage) {
     md.push_str("| command | description |\n");
     md.push_str("|:-|:-\n");
 
-    for sc in &page.subcommands {
+    for sc in page.subcommands.iter().filter(|sc| !sc.hidden) {
+        let mut names = vec![sc.name.as_str()];
+        names.extend(sc.aliases.iter().map(String::as_str));
+
         md.push_str(&format!(
             "| {} | {} |\n",
-            sc.name,
-            sc.summary.as_deref().unwrap_or("")
+            names.join(", "),
+            sc.summary.as_deref().map(first_line).unwrap_or_default()
         ));
     }
 
     md.push_str("|-\n");
-    md.push_str("\n");
+    md.push('\n');
 }
 
 fn render_positionals(md: &mut String, page: &HelpPage) {
-    if page.positionals.is_empty() {
+    if page.positionals.iter().all(|arg| arg.hidden) {
         return;
     }
 
     md.push_str("**Arguments:**\n");
 
-    for arg in &page.positionals {
+    for arg in page.positionals.iter().filter(|arg| !arg.hidden) {
         md.push_str(&format!(
-            "* `{}`: {} *({}{})*\n",
+            "* `{}`: {} *({}{})*",
             arg.name,
-            arg.description.as_deref().unwrap_or(""),
+            arg_help(
+                page,
+                arg.description.as_deref(),
+                arg.long_description.as_deref()
+            ),
             if arg.required {
                 "~~required~~"
             } else {
@@ -115,41 +895,116 @@ fn render_positionals(md: &mut String, page: &HelpPage) {
             },
             if arg.multiple { ", ~~multiple~~" } else { "" }
         ));
+
+        if let Some(badge) = arg.value.as_ref().and_then(value_badge) {
+            md.push_str(&format!(" {}", badge));
+        }
+        if let Some(values) = inline_values(&arg.possible_values) {
+            md.push_str(&format!(" {}", values));
+        }
+        if let Some(badges) =
+            relation_badges(page, false, &arg.conflicts, &arg.requires, &arg.groups)
+        {
+            md.push_str(&format!(" {}", badges));
+        }
+        if let Some(env) = &arg.env {
+            md.push_str(&format!(" {}", env_badge(env, page.show_env_values)));
+        }
+        md.push('\n');
     }
 
-    md.push_str("\n");
+    md.push('\n');
 }
 
+/// one table per `help_heading`, like clap: ungrouped options first under "Options", then each
+/// heading in the order it was first declared.
 fn render_options(md: &mut String, page: &HelpPage) {
-    if page.options.is_empty() {
+    let visible: Vec<&HelpOption> = page.options.iter().filter(|opt| !opt.hidden).collect();
+
+    let mut headings: Vec<&str> = vec!["Options"];
+    for opt in &visible {
+        let heading = opt.heading.as_deref().unwrap_or("Options");
+        if !headings.contains(&heading) {
+            headings.push(heading);
+        }
+    }
+
+    for heading in headings {
+        let options: Vec<&HelpOption> = visible
+            .iter()
+            .copied()
+            .filter(|opt| opt.heading.as_deref().unwrap_or("Options") == heading)
+            .collect();
+
+        render_option_table(md, page, heading, &options);
+    }
+}
+
+fn render_option_table(md: &mut String, page: &HelpPage, heading: &str, options: &[&HelpOption]) {
+    if options.is_empty() {
         return;
     }
 
-    md.push_str("**Options:**\n");
+    md.push_str(&format!("**{}:**\n", heading));
 
     md.push_str("|:-:|:-:|-\n");
     md.push_str("|short|long|description|\n");
     md.push_str("|:-:|:-|-\n");
 
-    for opt in &page.options {
+    for opt in options {
         let mut name_short = String::new();
         let mut name_long = String::new();
-        let mut desc = opt.description.clone();
+        let mut desc = arg_help(
+            page,
+            Some(&opt.description),
+            opt.long_description.as_deref(),
+        );
+
+        let shorts: Vec<String> = opt
+            .short
+            .iter()
+            .chain(&opt.short_aliases)
+            .map(|c| format!("-{}", c))
+            .collect();
+        name_short.push_str(&shorts.join(", "));
+
+        let longs: Vec<String> = opt
+            .long
+            .iter()
+            .chain(&opt.aliases)
+            .map(|l| format!("--{}", l))
+            .collect();
+        name_long.push_str(&longs.join(", "));
 
-        if let Some(short) = opt.short {
-            name_short.push_str(&format!("-{}", short));
+        if let Some(val) = &opt.value {
+            let placeholder = val.placeholder().to_ascii_lowercase();
+            if !name_short.is_empty() {
+                name_short.push_str(&format!(" ~~{}~~", placeholder));
+            }
+
+            name_long.push_str(&format!(" ~~{}~~", placeholder));
+
+            if let Some(badge) = value_badge(val) {
+                desc.push_str(&format!(" {}", badge));
+            }
         }
 
-        if let Some(long) = &opt.long {
-            name_long.push_str(&format!("--{}", long));
+        if let Some(values) = inline_values(&opt.possible_values) {
+            desc.push_str(&format!(" {}", values));
         }
 
-        if let Some(val) = &opt.value {
-            if !name_short.is_empty() {
-                name_short.push_str(&format!(" ~~<{}>~~", val.to_ascii_lowercase()));
-            }
+        if let Some(badges) = relation_badges(
+            page,
+            opt.required,
+            &opt.conflicts,
+            &opt.requires,
+            &opt.groups,
+        ) {
+            desc.push_str(&format!(" {}", badges));
+        }
 
-            name_long.push_str(&format!(" ~~<{}>~~", val.to_ascii_lowercase()));
+        if let Some(env) = &opt.env {
+            desc.push_str(&format!(" {}", env_badge(env, page.show_env_values)));
         }
 
         if !opt.default.is_empty() {
@@ -161,18 +1016,158 @@ fn render_options(md: &mut String, page: &HelpPage) {
 
     md.push_str("|-\n");
 
-    md.push_str("\n");
+    md.push('\n');
+}
+
+/// longest list of possible values still shown inline in a table row.
+const INLINE_VALUES: usize = 5;
+
+/// `[fast|safe|auto]` for short lists of (visible) possible values, a count for long ones.
+fn inline_values(values: &[HelpPossibleValue]) -> Option<String> {
+    let names: Vec<&str> = values
+        .iter()
+        .filter(|v| !v.hidden)
+        .map(|v| v.name.as_str())
+        .collect();
+
+    match names.len() {
+        0 => None,
+        // pipes are escaped so they don't split table cells.
+        n if n <= INLINE_VALUES => Some(format!("~~[{}]~~", names.join("\\|"))),
+        n => Some(format!("*({} possible values)*", n)),
+    }
+}
+
+/// `(path, 1..=10 values)`: the value kind and any count the placeholder doesn't show.
+fn value_badge(value: &HelpValue) -> Option<String> {
+    let parts: Vec<String> = value.hint.iter().cloned().chain(value.count()).collect();
+
+    (!parts.is_empty()).then(|| format!("*({})*", parts.join(", ")))
 }
 
-fn render_examples(md: &mut String, page: &HelpPage) {
-    if page.examples.is_empty() {
+/// `(env: APP_TOKEN, set)`, or `(env: APP_TOKEN=abc)` when the page shows values.
+fn env_badge(env: &HelpEnvVar, show_value: bool) -> String {
+    let state = match (env.value.as_ref().filter(|_| show_value), env.set) {
+        (Some(value), _) => format!("={}", escape_markdown(value)),
+        (None, true) => ", set".to_string(),
+        (None, false) => ", not set".to_string(),
+    };
+
+    format!("*(env: {}{})*", env.name, state)
+}
+
+/// backslash the characters that would start emphasis or split a table cell.
+fn escape_markdown(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        if matches!(c, '|' | '*' | '~' | '`' | '\\') {
+            out.push('\\');
+        }
+        out.push(c);
+    }
+    out
+}
+
+/// options inherited from parent commands, kept short since they're the same on every page.
+fn render_globals(md: &mut String, page: &HelpPage) {
+    let globals: Vec<&HelpOption> = page.globals.iter().filter(|opt| !opt.hidden).collect();
+
+    if globals.is_empty() {
+        return;
+    }
+
+    md.truncate(md.trim_end().len());
+    md.push_str("\n\n**Global options:**\n");
+
+    for opt in globals {
+        let mut name = match (opt.short, &opt.long) {
+            (Some(short), Some(long)) => format!("-{}, --{}", short, long),
+            (Some(short), None) => format!("-{}", short),
+            (None, Some(long)) => format!("--{}", long),
+            (None, None) => continue,
+        };
+        if let Some(value) = &opt.value {
+            name.push_str(&format!(" {}", value.placeholder().to_ascii_lowercase()));
+        }
+
+        let desc = arg_help(page, Some(&opt.description), None);
+        if desc.is_empty() {
+            md.push_str(&format!("* `{}`\n", name));
+        } else {
+            md.push_str(&format!("* `{}`: {}\n", name, desc));
+        }
+    }
+
+    md.push('\n');
+}
+
+/// hidden subcommands and arguments, dimmed, for show-hidden mode.
+fn render_hidden(md: &mut String, page: &HelpPage) {
+    if !page.show_hidden {
+        return;
+    }
+
+    let mut rows = Vec::new();
+
+    for sc in page.subcommands.iter().filter(|sc| sc.hidden) {
+        let summary = sc.summary.as_deref().map(first_line).unwrap_or_default();
+        rows.push((sc.name.clone(), summary.to_string()));
+    }
+
+    for arg in page.positionals.iter().filter(|arg| arg.hidden) {
+        rows.push((
+            format!("<{}>", arg.name),
+            arg_help(
+                page,
+                arg.description.as_deref(),
+                arg.long_description.as_deref(),
+            ),
+        ));
+    }
+
+    let options = page.options.iter().chain(&page.globals);
+    for opt in options.filter(|opt| opt.hidden) {
+        let name = match (opt.short, &opt.long) {
+            (Some(short), Some(long)) => format!("-{}, --{}", short, long),
+            (Some(short), None) => format!("-{}", short),
+            (None, Some(long)) => format!("--{}", long),
+            (None, None) => continue,
+        };
+        rows.push((
+            name,
+            arg_help(
+                page,
+                Some(&opt.description),
+                opt.long_description.as_deref(),
+            ),
+        ));
+    }
+
+    if rows.is_empty() {
+        return;
+    }
+
+    md.push_str("**Hidden:**\n");
+
+    for (name, desc) in rows {
+        if desc.is_empty() {
+            md.push_str(&format!("* *{}*\n", name));
+        } else {
+            md.push_str(&format!("* *{}: {}*\n", name, desc));
+        }
+    }
+
+    md.push('\n');
+}
+
+fn render_examples(md: &mut String, examples: &[String]) {
+    if examples.is_empty() {
         return;
     }
 
     md.push_str("**Examples:**\n");
 
-    let mark = page
-        .examples
+    let mark = examples
         .iter()
         .enumerate()
         .map(|(i, e)| format!("~~{})~~ {}", i + 1, e))
@@ -183,15 +1178,14 @@ fn render_examples(md: &mut String, page: &HelpPage) {
     md.push_str("\n\n");
 }
 
-fn render_notes(md: &mut String, page: &HelpPage) {
-    if page.notes.is_empty() {
+fn render_notes(md: &mut String, notes: &[String]) {
+    if notes.is_empty() {
         return;
     }
 
     md.push_str("**Notes:**\n");
 
-    let mark = page
-        .notes
+    let mark = notes
         .iter()
         .map(|n| format!("- {}", n))
         .collect::<Vec<_>>()
@@ -200,6 +1194,20 @@ fn render_notes(md: &mut String, page: &HelpPage) {
     md.push_str(&mark);
 }
 
+fn render_guide_hint(md: &mut String, page: &HelpPage) {
+    if page.guide_count == 0 {
+        return;
+    }
+
+    md.truncate(md.trim_end().len());
+    md.push_str(&format!(
+        "\n\n*{} guide{} available, list {} with* `help guides`\n",
+        page.guide_count,
+        if page.guide_count == 1 { "" } else { "s" },
+        if page.guide_count == 1 { "it" } else { "them" }
+    ));
+}
+
 fn view_area() -> termimad::Area {
     let mut area = termimad::Area::full_screen();
     if area.width <= 120 {
@@ -213,7 +1221,8 @@ fn draw_vertical_legend<W: Write>(
     out: &mut W,
     app_name: &str,
     accent: Color,
-) -> anyhow::Result<()> {
+    colored: bool,
+) -> std::io::Result<()> {
     let legend = [
         format!("{}", format!("{app_name} Help").with(accent).bold()),
         format!(
@@ -244,9 +1253,17 @@ fn draw_vertical_legend<W: Write>(
     let y = 0;
 
     for (i, line) in legend.iter().enumerate() {
-        out.queue(termimad::crossterm::style::SetBackgroundColor(
-            termimad::crossterm::style::Color::Black,
-        ))?;
+        if colored {
+            out.queue(termimad::crossterm::style::SetBackgroundColor(
+                termimad::crossterm::style::Color::Black,
+            ))?;
+        }
+
+        let line = if colored {
+            line.clone()
+        } else {
+            strip_ansi(line)
+        };
 
         out.queue(termimad::crossterm::cursor::MoveTo(x, y + i as u16))?;
         out.queue(termimad::crossterm::style::Print(line))?;
@@ -264,6 +1281,10 @@ fn visible_width(s: &str) -> usize {
     unicode_width::UnicodeWidthStr::width(stripped)
 }
 
+fn strip_ansi(s: &str) -> String {
+    String::from_utf8(strip_ansi_escapes::strip(s)).unwrap_or_else(|_| s.to_string())
+}
+
 fn join_justify_between(items: &[String], width: u16) -> String {
     let visible_total: usize = items.iter().map(|s| visible_width(s)).sum();
     let gaps = items.len().saturating_sub(1);
@@ -295,7 +1316,8 @@ fn draw_horizontal_legend<W: Write>(
     area: &termimad::Area,
     app_name: &str,
     accent: Color,
-) -> anyhow::Result<()> {
+    colored: bool,
+) -> std::io::Result<()> {
     let items = [
         format!("{}", format!("{app_name} Help").with(accent).bold()),
         format!(
@@ -322,7 +1344,10 @@ fn draw_horizontal_legend<W: Write>(
         ),
     ];
 
-    let line = join_justify_between(&items, area.width);
+    let mut line = join_justify_between(&items, area.width);
+    if !colored {
+        line = strip_ansi(&line);
+    }
 
     out.queue(termimad::crossterm::cursor::MoveTo(0, area.height - 1))?;
     out.queue(termimad::crossterm::style::Print(line))?;
@@ -335,11 +1360,12 @@ fn draw_legend<W: Write>(
     area: &termimad::Area,
     app_name: &str,
     accent: Color,
-) -> anyhow::Result<()> {
+    colored: bool,
+) -> std::io::Result<()> {
     if area.width > 120 {
-        draw_vertical_legend(out, app_name, accent)
+        draw_vertical_legend(out, app_name, accent, colored)
     } else {
-        draw_horizontal_legend(out, area, app_name, accent)
+        draw_horizontal_legend(out, area, app_name, accent, colored)
     }
 }
 
@@ -348,10 +1374,19 @@ pub fn run_scrollable_help(
     theme: &HelpTheme,
     app_name: &str,
     markdown: String,
-) -> anyhow::Result<()> {
+) -> Result<(), HelpError> {
     let mut w = stdout();
+    if !w.is_terminal() {
+        return Err(HelpError::TerminalUnavailable(std::io::Error::other(
+            "stdout is not a terminal",
+        )));
+    }
+
+    let theme = theme.for_terminal();
+    let colored = theme.use_color(true);
+
     queue!(w, termimad::crossterm::terminal::EnterAlternateScreen)?;
-    termimad::crossterm::terminal::enable_raw_mode()?;
+    termimad::crossterm::terminal::enable_raw_mode().map_err(HelpError::TerminalUnavailable)?;
     queue!(w, termimad::crossterm::cursor::Hide)?;
     let mut view = MadView::from(markdown, view_area(), theme.skin.clone());
 
@@ -359,7 +1394,7 @@ pub fn run_scrollable_help(
 
     loop {
         view.write_on(&mut w)?;
-        draw_legend(&mut stdout(), &term_area, app_name, theme.accent)?;
+        draw_legend(&mut stdout(), &term_area, app_name, theme.accent, colored)?;
         w.flush()?;
         match event::read() {
             Ok(Event::Key(event::KeyEvent { code, .. })) => match code {
@@ -394,3 +1429,56 @@ pub fn run_scrollable_help(
     w.flush()?;
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn env(set: bool, value: Option<&str>) -> HelpEnvVar {
+        HelpEnvVar {
+            name: "APP_TOKEN".to_string(),
+            set,
+            value: value.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn env_badge_hides_the_value_unless_asked() {
+        let token = env(true, Some("hunter2"));
+
+        assert_eq!(env_badge(&token, false), "*(env: APP_TOKEN, set)*");
+        assert_eq!(env_badge(&token, true), "*(env: APP_TOKEN=hunter2)*");
+        assert_eq!(
+            env_badge(&env(false, None), true),
+            "*(env: APP_TOKEN, not set)*"
+        );
+    }
+
+    #[test]
+    fn env_badge_escapes_the_value() {
+        let token = env(true, Some("a|b*c"));
+
+        assert_eq!(env_badge(&token, true), "*(env: APP_TOKEN=a\\|b\\*c)*");
+    }
+
+    #[test]
+    fn hide_env_values_wins_over_show_env_values() {
+        // `hide_env_values` leaves `value` empty even though the var is set.
+        assert_eq!(env_badge(&env(true, None), true), "*(env: APP_TOKEN, set)*");
+    }
+
+    #[test]
+    fn argument_page_hides_the_value_unless_asked() {
+        let cmd = clap::Command::new("app").arg(clap::Arg::new("token").long("token"));
+        let arg = cmd.get_arguments().next().unwrap();
+        let mut page = HelpArgumentPage::from_clap("app", "", &cmd, arg);
+        page.env = Some(env(true, Some("hunter2")));
+
+        let md = argument_markdown(&page);
+        assert!(md.contains("**Environment:** `APP_TOKEN` *(currently set)*"));
+        assert!(!md.contains("hunter2"));
+
+        let md = argument_markdown(&page.with_show_env_values(true));
+        assert!(md.contains("**Environment:** `APP_TOKEN` *(currently `hunter2`)*"));
+    }
+}
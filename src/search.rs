@@ -0,0 +1,339 @@
+//! full-text search across the clap tree, command docs and guides (`help search <terms>`).
+//!
+//! every command, argument and guide becomes an entry with a few weighted fields. an entry
+//! matches when every term shows up in at least one field (case-insensitive), and scores by the
+//! best field each term was found in.
+
+use clap::{Arg, Command};
+
+use crate::doc_registry::DocRegistry;
+use crate::help_page::HelpGuideEntry;
+use crate::relations;
+
+const NAME_WEIGHT: u32 = 10;
+const SUMMARY_WEIGHT: u32 = 5;
+const BODY_WEIGHT: u32 = 2;
+
+const SNIPPET_RADIUS: usize = 40;
+
+/// one search result.
+#[derive(Debug, Clone)]
+pub struct SearchHit {
+    /// topic that opens the result (e.g. `help commit --message`)
+    pub topic: String,
+
+    /// what kind of thing matched ("command", "option", "argument" or "guide")
+    pub kind: &'static str,
+
+    /// excerpt around the first match, with matches wrapped in `~~` for the accent color
+    pub snippet: String,
+
+    pub score: u32,
+}
+
+struct Entry {
+    topic: String,
+    kind: &'static str,
+    fields: Vec<(u32, String)>,
+}
+
+/// rank every command, argument and guide against `terms`, best first.
+pub fn search(
+    root: &Command,
+    docs: &DocRegistry,
+    app_name: &str,
+    terms: &[String],
+) -> Vec<SearchHit> {
+    let terms: Vec<String> = terms
+        .iter()
+        .map(|t| t.to_ascii_lowercase())
+        .filter(|t| !t.is_empty())
+        .collect();
+
+    if terms.is_empty() {
+        return Vec::new();
+    }
+
+    let mut entries = Vec::new();
+    index_command(&mut entries, docs, root, &[]);
+    index_guides(&mut entries, docs, app_name);
+
+    let mut hits: Vec<SearchHit> = entries
+        .into_iter()
+        .filter_map(|entry| score(&entry, &terms))
+        .collect();
+
+    hits.sort_by(|a, b| b.score.cmp(&a.score).then_with(|| a.topic.cmp(&b.topic)));
+    hits
+}
+
+fn index_command(entries: &mut Vec<Entry>, docs: &DocRegistry, cmd: &Command, path: &[String]) {
+    let key = path.join(".");
+
+    if !path.is_empty() {
+        let mut fields = vec![(NAME_WEIGHT, cmd.get_name().to_string())];
+        fields.extend(
+            cmd.get_visible_aliases()
+                .map(|a| (NAME_WEIGHT, a.to_string())),
+        );
+        fields.extend(cmd.get_about().map(|s| (SUMMARY_WEIGHT, s.to_string())));
+        fields.extend(cmd.get_long_about().map(|s| (BODY_WEIGHT, s.to_string())));
+        push_doc_fields(&mut fields, docs, &key);
+
+        entries.push(Entry {
+            topic: topic(path),
+            kind: "command",
+            fields,
+        });
+    }
+
+    for arg in cmd.get_arguments().filter(|a| !a.is_hide_set()) {
+        index_argument(entries, docs, path, &key, arg);
+    }
+
+    for sc in cmd.get_subcommands().filter(|sc| !sc.is_hide_set()) {
+        let mut sub_path = path.to_vec();
+        sub_path.push(sc.get_name().to_string());
+        index_command(entries, docs, sc, &sub_path);
+    }
+}
+
+fn index_argument(
+    entries: &mut Vec<Entry>,
+    docs: &DocRegistry,
+    path: &[String],
+    key: &str,
+    arg: &Arg,
+) {
+    let name = relations::argument_name(arg);
+
+    let mut fields = vec![(NAME_WEIGHT, name.clone())];
+    fields.extend(arg.get_help().map(|s| (SUMMARY_WEIGHT, s.to_string())));
+    fields.extend(arg.get_long_help().map(|s| (BODY_WEIGHT, s.to_string())));
+
+    push_doc_fields(&mut fields, docs, &relations::argument_key(key, arg));
+
+    let mut arg_path = path.to_vec();
+    arg_path.push(name);
+
+    entries.push(Entry {
+        topic: topic(&arg_path),
+        kind: if arg.is_positional() {
+            "argument"
+        } else {
+            "option"
+        },
+        fields,
+    });
+}
+
+fn index_guides(entries: &mut Vec<Entry>, docs: &DocRegistry, app_name: &str) {
+    for key in docs.guide_keys() {
+        // the program guides may be keyed by the app name, list them as the root.
+        let path = if key == app_name { "" } else { key };
+
+        let default = docs.guide(key).map(|content| (None, content));
+        let named = docs
+            .guide_names(key)
+            .into_iter()
+            .filter_map(|name| docs.named_guide(key, name).map(|c| (Some(name), c)));
+
+        for (name, content) in default.into_iter().chain(named) {
+            let guide = HelpGuideEntry::new(path, name, content);
+
+            let mut fields = Vec::new();
+            fields.extend(name.map(|n| (NAME_WEIGHT, n.to_string())));
+            fields.extend(guide.title.clone().map(|t| (SUMMARY_WEIGHT, t)));
+            fields.push((BODY_WEIGHT, content.to_string()));
+
+            entries.push(Entry {
+                topic: guide.topic(),
+                kind: "guide",
+                fields,
+            });
+        }
+    }
+}
+
+fn push_doc_fields(fields: &mut Vec<(u32, String)>, docs: &DocRegistry, key: &str) {
+    if let Some(doc) = docs.command(key) {
+        fields.extend(doc.description.clone().map(|d| (SUMMARY_WEIGHT, d)));
+        fields.extend(doc.examples.iter().map(|e| (BODY_WEIGHT, e.clone())));
+        fields.extend(doc.notes.iter().map(|n| (BODY_WEIGHT, n.clone())));
+    }
+}
+
+fn topic(path: &[String]) -> String {
+    std::iter::once("help")
+        .chain(path.iter().map(String::as_str))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn score(entry: &Entry, terms: &[String]) -> Option<SearchHit> {
+    let mut total = 0;
+
+    for term in terms {
+        total += entry
+            .fields
+            .iter()
+            .filter(|(_, text)| text.to_ascii_lowercase().contains(term.as_str()))
+            .map(|(weight, _)| *weight)
+            .max()?;
+    }
+
+    // show the most descriptive field that matched, rather than the bare name.
+    let snippet_source = entry
+        .fields
+        .iter()
+        .filter(|(_, text)| {
+            let lowered = text.to_ascii_lowercase();
+            terms.iter().any(|t| lowered.contains(t.as_str()))
+        })
+        .min_by_key(|(weight, _)| *weight)
+        .map(|(_, text)| text.as_str())
+        .unwrap_or_default();
+
+    Some(SearchHit {
+        topic: entry.topic.clone(),
+        kind: entry.kind,
+        snippet: snippet(snippet_source, terms),
+        score: total,
+    })
+}
+
+/// excerpt of `text` around the first match, flattened to one line with matches highlighted.
+fn snippet(text: &str, terms: &[String]) -> String {
+    // markdown markers in the source would fight with our own highlighting.
+    let flat: String = text
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .chars()
+        .filter(|c| !matches!(c, '`' | '*' | '~' | '|' | '#'))
+        .collect();
+    let lowered = flat.to_ascii_lowercase();
+
+    let first = terms
+        .iter()
+        .filter_map(|t| lowered.find(t.as_str()))
+        .min()
+        .unwrap_or(0);
+
+    let start = floor_char_boundary(&flat, first.saturating_sub(SNIPPET_RADIUS));
+    let end = floor_char_boundary(&flat, (first + SNIPPET_RADIUS * 2).min(flat.len()));
+
+    let mut out = String::new();
+    if start > 0 {
+        out.push_str("...");
+    }
+    out.push_str(&highlight(&flat[start..end], &lowered[start..end], terms));
+    if end < flat.len() {
+        out.push_str("...");
+    }
+    out
+}
+
+fn highlight(text: &str, lowered: &str, terms: &[String]) -> String {
+    let mut out = String::new();
+    let mut i = 0;
+
+    while i < text.len() {
+        let matched = terms
+            .iter()
+            .filter(|t| lowered[i..].starts_with(t.as_str()))
+            .map(String::len)
+            .max();
+
+        match matched {
+            Some(len) => {
+                out.push_str(&format!("~~{}~~", &text[i..i + len]));
+                i += len;
+            }
+            None => {
+                let c = text[i..].chars().next().unwrap_or_default();
+                out.push(c);
+                i += c.len_utf8();
+            }
+        }
+    }
+
+    out
+}
+
+fn floor_char_boundary(s: &str, mut index: usize) -> usize {
+    while !s.is_char_boundary(index) {
+        index -= 1;
+    }
+    index
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn terms(terms: &[&str]) -> Vec<String> {
+        terms.iter().map(|t| t.to_string()).collect()
+    }
+
+    #[test]
+    fn highlight_keeps_the_original_case() {
+        let text = "Set the Message";
+        assert_eq!(
+            highlight(text, &text.to_ascii_lowercase(), &terms(&["message"])),
+            "Set the ~~Message~~"
+        );
+    }
+
+    #[test]
+    fn highlight_prefers_the_longest_term() {
+        let text = "message";
+        assert_eq!(
+            highlight(text, text, &terms(&["mess", "message"])),
+            "~~message~~"
+        );
+    }
+
+    #[test]
+    fn highlight_handles_non_ascii() {
+        let text = "Héllo wörld";
+        assert_eq!(
+            highlight(text, &text.to_ascii_lowercase(), &terms(&["wörld"])),
+            "Héllo ~~wörld~~"
+        );
+    }
+
+    #[test]
+    fn snippet_flattens_markdown_and_whitespace() {
+        assert_eq!(
+            snippet("the `commit`\n\n  **message**", &terms(&["message"])),
+            "the commit ~~message~~"
+        );
+    }
+
+    #[test]
+    fn snippet_cuts_around_the_first_match() {
+        let text = format!("{} needle {}", "word ".repeat(30), "word ".repeat(30));
+        let snippet = snippet(&text, &terms(&["needle"]));
+
+        assert!(snippet.starts_with("..."));
+        assert!(snippet.ends_with("..."));
+        assert!(snippet.contains("~~needle~~"));
+    }
+
+    #[test]
+    fn snippet_cuts_on_char_boundaries() {
+        // both cut points land in the middle of a two-byte character.
+        let text = format!("{} needle {}", "é".repeat(50), "ü".repeat(50));
+        let snippet = snippet(&text, &terms(&["needle"]));
+
+        assert!(snippet.starts_with("...é"));
+        assert!(snippet.ends_with("ü..."));
+        assert!(snippet.contains("~~needle~~"));
+    }
+
+    #[test]
+    fn snippet_without_a_match_starts_at_the_beginning() {
+        assert_eq!(snippet("short text", &terms(&["nope"])), "short text");
+    }
+}
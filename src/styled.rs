@@ -0,0 +1,188 @@
+//! clap's `StyledStr` as markdown, so styled help text keeps its emphasis.
+
+use clap::builder::StyledStr;
+
+/// the parts of an SGR style markdown can express.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+struct Style {
+    bold: bool,
+    italic: bool,
+    color: bool,
+}
+
+impl Style {
+    /// apply the parameters of one `ESC [ ... m` sequence.
+    fn apply(mut self, params: &str) -> Self {
+        let mut codes = params.split(';').map(|c| c.parse::<u8>().unwrap_or(0));
+
+        while let Some(code) = codes.next() {
+            match code {
+                0 => self = Self::default(),
+                1 => self.bold = true,
+                22 => self.bold = false,
+                // markdown has no underline, italic is the closest.
+                3 | 4 => self.italic = true,
+                23 | 24 => self.italic = false,
+                30..=37 | 90..=97 => self.color = true,
+                39 => self.color = false,
+                38 | 48 => {
+                    // 256-color and rgb forms carry their own arguments.
+                    match codes.next() {
+                        Some(5) => {
+                            codes.next();
+                        }
+                        Some(2) => {
+                            codes.nth(2);
+                        }
+                        _ => {}
+                    }
+                    if code == 38 {
+                        self.color = true;
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        self
+    }
+
+    /// markdown markers for this style, outermost first. colors map to the accent (`~~`).
+    fn markers(self) -> Vec<&'static str> {
+        [(self.bold, "**"), (self.italic, "*"), (self.color, "~~")]
+            .into_iter()
+            .filter(|(on, _)| *on)
+            .map(|(_, marker)| marker)
+            .collect()
+    }
+}
+
+/// close the markers that no longer apply (and the ones nested inside them), then open the
+/// missing ones.
+fn restyle(md: &mut String, open: &mut Vec<&'static str>, style: Style) {
+    let wanted = style.markers();
+
+    if let Some(pos) = open.iter().position(|m| !wanted.contains(m)) {
+        for marker in open.drain(pos..).rev() {
+            md.push_str(marker);
+        }
+    }
+
+    for marker in wanted {
+        if !open.contains(&marker) {
+            md.push_str(marker);
+            open.push(marker);
+        }
+    }
+}
+
+/// `text` with bold, italic/underline and colored spans turned into `**`, `*` and `~~`.
+///
+/// any other escape sequence is dropped, so the result is safe to hand to termimad.
+pub(crate) fn to_markdown(text: &StyledStr) -> String {
+    let ansi = text.ansi().to_string();
+    let mut md = String::with_capacity(ansi.len());
+    let mut open = Vec::new();
+    let mut style = Style::default();
+    let mut rest = ansi.as_str();
+
+    loop {
+        let (chunk, tail) = rest.split_at(rest.find('\x1b').unwrap_or(rest.len()));
+
+        // only style text that's actually there, so empty spans don't leave `****` behind.
+        if !chunk.is_empty() {
+            restyle(&mut md, &mut open, style);
+            md.push_str(chunk);
+        }
+
+        let Some(seq) = tail.strip_prefix("\x1b[") else {
+            // end of text, or a lone escape that isn't a CSI sequence.
+            match tail.get(1..) {
+                Some(next) if !next.is_empty() => {
+                    rest = next;
+                    continue;
+                }
+                _ => break,
+            }
+        };
+
+        let Some(end) = seq.find(|c: char| c.is_ascii_alphabetic()) else {
+            break;
+        };
+        if seq[end..].starts_with('m') {
+            style = style.apply(&seq[..end]);
+        }
+        rest = &seq[end + 1..];
+    }
+
+    restyle(&mut md, &mut open, Style::default());
+    md
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn md(ansi: &str) -> String {
+        to_markdown(&StyledStr::from(ansi.to_string()))
+    }
+
+    #[test]
+    fn plain_text_is_untouched() {
+        assert_eq!(md("just text"), "just text");
+    }
+
+    #[test]
+    fn bold_italic_and_color() {
+        assert_eq!(md("\x1b[1mbold\x1b[0m"), "**bold**");
+        assert_eq!(md("\x1b[4munderlined\x1b[24m"), "*underlined*");
+        assert_eq!(md("\x1b[32mgreen\x1b[39m text"), "~~green~~ text");
+    }
+
+    #[test]
+    fn color_nested_in_bold() {
+        assert_eq!(
+            md("\x1b[1mbold \x1b[31mred\x1b[39m done\x1b[0m"),
+            "**bold ~~red~~ done**"
+        );
+    }
+
+    #[test]
+    fn closing_the_outer_style_reopens_the_inner_one() {
+        assert_eq!(
+            md("\x1b[1;31mboth\x1b[22m red\x1b[0m"),
+            "**~~both~~**~~ red~~"
+        );
+    }
+
+    #[test]
+    fn extended_color_arguments_are_not_codes() {
+        // the `1`, `3` and `4` are palette / rgb values here, not bold or italic.
+        assert_eq!(md("\x1b[38;5;1mx\x1b[0m"), "~~x~~");
+        assert_eq!(md("\x1b[38;2;1;3;4mx\x1b[0m"), "~~x~~");
+        assert_eq!(md("\x1b[48;5;1mx\x1b[0m"), "x");
+    }
+
+    #[test]
+    fn empty_spans_and_other_sequences_are_dropped() {
+        assert_eq!(md("\x1b[1m\x1b[0mtext"), "text");
+        assert_eq!(md("\x1b[2Kline"), "line");
+    }
+
+    #[test]
+    fn apply_tracks_each_attribute() {
+        let style = Style::default().apply("1;38;2;1;3;4");
+        assert_eq!(
+            style,
+            Style {
+                bold: true,
+                italic: false,
+                color: true,
+            }
+        );
+
+        assert_eq!(style.apply("22").markers(), vec!["~~"]);
+        assert_eq!(style.apply("39;3").markers(), vec!["**", "*"]);
+        assert_eq!(style.apply("0"), Style::default());
+    }
+}
@@ -0,0 +1,101 @@
+//! fuzzy matching for "did you mean ..." hints when a help topic doesn't resolve.
+
+/// pick the candidates that look close enough to `input`, best match first.
+///
+/// a candidate qualifies if one is a prefix of the other or if it is within a small edit distance
+/// (roughly a third of the input length, at least one edit).
+pub(crate) fn did_you_mean<'a, I>(input: &str, candidates: I) -> Vec<String>
+where
+    I: IntoIterator<Item = &'a str>,
+{
+    let input = input.to_lowercase();
+    let max_distance = (input.chars().count() / 3).max(1);
+
+    let mut scored: Vec<(usize, &str)> = candidates
+        .into_iter()
+        .filter_map(|candidate| {
+            let lowered = candidate.to_lowercase();
+            if lowered.starts_with(&input) || input.starts_with(&lowered) {
+                return Some((0, candidate));
+            }
+
+            let distance = levenshtein(&input, &lowered);
+            (distance <= max_distance).then_some((distance, candidate))
+        })
+        .collect();
+
+    scored.sort_by(|a, b| a.0.cmp(&b.0).then_with(|| a.1.cmp(b.1)));
+
+    let mut out: Vec<String> = Vec::new();
+    for (_, candidate) in scored {
+        if !out.iter().any(|c| c == candidate) {
+            out.push(candidate.to_string());
+        }
+    }
+    out
+}
+
+fn levenshtein(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut prev: Vec<usize> = (0..=b.len()).collect();
+    let mut cur = vec![0; b.len() + 1];
+
+    for (i, ca) in a.chars().enumerate() {
+        cur[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let cost = usize::from(ca != *cb);
+            cur[j + 1] = (prev[j + 1] + 1).min(cur[j] + 1).min(prev[j] + cost);
+        }
+        std::mem::swap(&mut prev, &mut cur);
+    }
+
+    prev[b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn levenshtein_counts_edits() {
+        assert_eq!(levenshtein("", ""), 0);
+        assert_eq!(levenshtein("commit", "commit"), 0);
+        assert_eq!(levenshtein("comit", "commit"), 1);
+        assert_eq!(levenshtein("kitten", "sitting"), 3);
+        assert_eq!(levenshtein("", "abc"), 3);
+        // characters, not bytes.
+        assert_eq!(levenshtein("naïve", "naive"), 1);
+    }
+
+    #[test]
+    fn typos_and_prefixes_match() {
+        let candidates = ["commit", "config", "clone", "status"];
+
+        assert_eq!(did_you_mean("comit", candidates), vec!["commit"]);
+        assert_eq!(did_you_mean("stat", candidates), vec!["status"]);
+        assert_eq!(did_you_mean("CLONE", candidates), vec!["clone"]);
+    }
+
+    #[test]
+    fn closest_first_then_alphabetical() {
+        assert_eq!(
+            did_you_mean("pus", ["pull", "pushy", "push", "pus"]),
+            vec!["pus", "push", "pushy"]
+        );
+        assert_eq!(
+            did_you_mean("checkot", ["checks", "checkout"]),
+            vec!["checkout", "checks"]
+        );
+    }
+
+    #[test]
+    fn far_off_input_gives_nothing() {
+        assert!(did_you_mean("zzz", ["commit", "status"]).is_empty());
+        assert!(did_you_mean("x", std::iter::empty()).is_empty());
+    }
+
+    #[test]
+    fn duplicates_are_dropped() {
+        assert_eq!(did_you_mean("sta", ["status", "status"]), vec!["status"]);
+    }
+}
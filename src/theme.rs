@@ -1,6 +1,6 @@
 //! small wrapper around `termimad::MadSkin` so you can pick an accent and move on.
 
-use termimad::crossterm::style::Color;
+use termimad::crossterm::style::{Attribute, Color};
 use termimad::{Alignment, CompoundStyle, MadSkin};
 
 /// accent-aware theme used by the renderer.
@@ -8,13 +8,107 @@ use termimad::{Alignment, CompoundStyle, MadSkin};
 pub struct HelpTheme {
     pub accent: Color,
     pub skin: MadSkin,
+
+    /// when to use colors at all (auto by default).
+    pub color: ColorPolicy,
+}
+
+/// when help output gets colors.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ColorPolicy {
+    /// colors on terminals, unless `NO_COLOR` or `CLICOLOR=0` say otherwise (`CLICOLOR_FORCE`
+    /// turns them on for pipes too).
+    #[default]
+    Auto,
+
+    Always,
+
+    /// bold and tables only.
+    Never,
+}
+
+impl ColorPolicy {
+    /// whether to color a stream that is (or isn't) a terminal.
+    pub fn enabled(self, terminal: bool) -> bool {
+        match self {
+            ColorPolicy::Always => true,
+            ColorPolicy::Never => false,
+            ColorPolicy::Auto => {
+                // NO_COLOR wins even over CLICOLOR_FORCE, crossterm drops colors under it anyway.
+                if std::env::var_os("NO_COLOR").is_some_and(|v| !v.is_empty()) {
+                    false
+                } else if env_flag("CLICOLOR_FORCE") {
+                    true
+                } else {
+                    terminal && std::env::var_os("CLICOLOR").is_none_or(|v| v != "0")
+                }
+            }
+        }
+    }
+}
+
+impl From<clap::ColorChoice> for ColorPolicy {
+    fn from(choice: clap::ColorChoice) -> Self {
+        match choice {
+            clap::ColorChoice::Auto => ColorPolicy::Auto,
+            clap::ColorChoice::Always => ColorPolicy::Always,
+            clap::ColorChoice::Never => ColorPolicy::Never,
+        }
+    }
+}
+
+fn env_flag(name: &str) -> bool {
+    std::env::var_os(name).is_some_and(|v| !v.is_empty() && v != "0")
 }
 
 impl HelpTheme {
     /// apply an accent to an existing `MadSkin`.
     pub fn new(mut skin: MadSkin, accent: Color) -> Self {
         apply_accent(&mut skin, accent);
-        Self { accent, skin }
+        Self {
+            accent,
+            skin,
+            color: ColorPolicy::Auto,
+        }
+    }
+
+    /// same theme with another [`ColorPolicy`].
+    pub fn with_color(mut self, color: ColorPolicy) -> Self {
+        self.color = color;
+        self
+    }
+
+    /// whether to color a stream that is (or isn't) a terminal, see [`ColorPolicy::enabled`].
+    pub fn use_color(&self, terminal: bool) -> bool {
+        self.color.enabled(terminal)
+    }
+
+    /// the same layout without any colors: bold, italics, alignment and table borders stay.
+    pub fn monochrome(&self) -> Self {
+        let mut skin = MadSkin::no_style();
+        skin.bold.add_attr(Attribute::Bold);
+        skin.italic.add_attr(Attribute::Italic);
+        skin.table_border_chars = self.skin.table_border_chars;
+
+        for (header, styled) in skin.headers.iter_mut().zip(&self.skin.headers) {
+            header.align = styled.align;
+            header.add_attr(Attribute::Bold);
+        }
+
+        Self {
+            accent: Color::Reset,
+            skin,
+            color: ColorPolicy::Never,
+        }
+    }
+
+    /// this theme as it should look on a terminal: unchanged, or monochrome with colors off.
+    pub(crate) fn for_terminal(&self) -> Self {
+        if self.use_color(true) {
+            self.clone()
+        } else {
+            self.monochrome()
+        }
     }
 
     /// light preset respecting the accent.
@@ -29,7 +123,7 @@ impl HelpTheme {
 
     /// chooses light/dark based on terminal luminance.
     pub fn default(accent: Color) -> Self {
-        if terminal_light::luma().map_or(false, |luma| luma > 0.6) {
+        if terminal_light::luma().is_ok_and(|luma| luma > 0.6) {
             Self::light(accent)
         } else {
             Self::dark(accent)